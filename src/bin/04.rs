@@ -1,7 +1,9 @@
-use std::fmt::Write;
-use std::ops::AddAssign;
+use advent_of_code::template::{ANSI_BOLD, ANSI_RESET};
+use glam::IVec2;
+use itertools::Itertools;
+use rustc_hash::FxHashSet;
 
-advent_of_code::solution!(4);
+advent_of_code::solution!(4, modes: { "--highlight" => highlight });
 
 pub fn part_one(input: &str) -> Option<u32> {
     Some(find_xmas(input))
@@ -11,121 +13,181 @@ pub fn part_two(input: &str) -> Option<u32> {
     Some(find_mas_in_x(input))
 }
 
-fn as_matrix(input: &str) -> Box<[Box<[char]>]> {
-    input.lines().map(|line| line.chars().collect()).collect()
+fn find_xmas(input: &str) -> u32 {
+    let grid = Grid::new(input);
+    grid.find(&Stencil::word("XMAS")).count() as u32
 }
 
-fn find_xmas(input: &str) -> u32 {
-    let matrix = as_matrix(input);
-    let mut count = 0;
-    let cols = matrix[0].len();
-    let rows = matrix.len();
-    let horizontal = String::with_capacity(4);
-    let vertical = String::with_capacity(4);
-    let diagonal_tl2br = String::with_capacity(4);
-    let diagonal_bl2tr = String::with_capacity(4);
-    let mut net = [horizontal, vertical, diagonal_tl2br, diagonal_bl2tr];
-    for col in 0..cols {
-        for row in 0..rows {
-            fill_net(&matrix, &mut net, (col, row));
-            count.add_assign(
-                net.iter()
-                    .filter(|&s| matches!(s.as_str(), "XMAS" | "SAMX"))
-                    .count() as u32,
-            );
-            net.iter_mut().for_each(|s| s.clear());
+fn find_mas_in_x(input: &str) -> u32 {
+    let grid = Grid::new(input);
+    grid.find(&Stencil::pattern("M.S\n.A.\nM.S")).count() as u32
+}
+
+/// Prints the grid with the letters of all matches of both parts highlighted, e.g.
+/// `cargo solve 4 -- --highlight`.
+fn highlight(input: &str) {
+    let grid = Grid::new(input);
+    for (part, stencils) in [
+        ("XMAS", Stencil::word("XMAS")),
+        ("X-MAS", Stencil::pattern("M.S\n.A.\nM.S")),
+    ] {
+        let matched: FxHashSet<Pos> = grid.find(&stencils).flat_map(|m| m.positions()).collect();
+        println!("{part}:");
+        for y in 0..grid.height as i32 {
+            let line: String = (0..grid.width as i32)
+                .map(|x| {
+                    let c = grid.get(Pos::new(x, y)).unwrap() as char;
+                    if matched.contains(&Pos::new(x, y)) {
+                        format!("{ANSI_BOLD}{c}{ANSI_RESET}")
+                    } else {
+                        ".".to_string()
+                    }
+                })
+                .collect();
+            println!("{line}");
         }
+        println!();
     }
-    count
 }
 
-fn fill_net(matrix: &[Box<[char]>], net: &mut [String; 4], (col, row): (usize, usize)) {
-    let cols = matrix[col].len();
-    let rows = matrix.len();
-    if col + 3 < cols {
-        write!(
-            &mut net[0],
-            "{}{}{}{}",
-            matrix[row][col],
-            matrix[row][col + 1],
-            matrix[row][col + 2],
-            matrix[row][col + 3]
-        )
-        .unwrap();
-    }
-    if row + 3 < rows {
-        write!(
-            &mut net[1],
-            "{}{}{}{}",
-            matrix[row][col],
-            matrix[row + 1][col],
-            matrix[row + 2][col],
-            matrix[row + 3][col]
-        )
-        .unwrap();
+type Pos = IVec2;
+
+/// The input as a byte grid, without copying it.
+struct Grid<'a> {
+    bytes: &'a [u8],
+    width: usize,
+    height: usize,
+}
+
+impl<'a> Grid<'a> {
+    fn new(input: &'a str) -> Self {
+        let width = input.find('\n').unwrap_or(input.len());
+        let height = input.lines().count();
+        Grid {
+            bytes: input.as_bytes(),
+            width,
+            height,
+        }
     }
-    if col + 3 < cols && row + 3 < rows {
-        write!(
-            &mut net[2],
-            "{}{}{}{}",
-            matrix[row][col],
-            matrix[row + 1][col + 1],
-            matrix[row + 2][col + 2],
-            matrix[row + 3][col + 3]
-        )
-        .unwrap();
+
+    #[inline]
+    fn get(&self, pos: Pos) -> Option<u8> {
+        let (x, y) = (usize::try_from(pos.x).ok()?, usize::try_from(pos.y).ok()?);
+        // every line is followed by a newline.
+        (x < self.width && y < self.height).then(|| self.bytes[y * (self.width + 1) + x])
     }
-    if col >= 3 && row + 3 < rows {
-        write!(
-            &mut net[3],
-            "{}{}{}{}",
-            matrix[row][col],
-            matrix[row + 1][col - 1],
-            matrix[row + 2][col - 2],
-            matrix[row + 3][col - 3]
-        )
-        .unwrap();
+
+    /// Finds every placement of any of the `stencils`, scanning each cell as the anchor.
+    fn find<'s>(&'s self, stencils: &'s [Stencil]) -> impl Iterator<Item = Match<'s>> + 's {
+        (0..self.height as i32)
+            .cartesian_product(0..self.width as i32)
+            .flat_map(move |(y, x)| {
+                let anchor = Pos::new(x, y);
+                stencils
+                    .iter()
+                    .filter(move |stencil| stencil.matches(self, anchor))
+                    .map(move |stencil| Match { anchor, stencil })
+            })
     }
 }
 
-fn find_mas_in_x(input: &str) -> u32 {
-    let matrix = as_matrix(input);
-    let mut count = 0;
-    let cols = matrix[0].len();
-    let rows = matrix.len();
-    let diagonal_1 = String::with_capacity(4);
-    let diagonal_2 = String::with_capacity(4);
-    let mut net = [diagonal_1, diagonal_2];
-    for col in 0..cols - 2 {
-        for row in 0..rows - 2 {
-            fill_net_x(&matrix, &mut net, (col, row));
-            if matches!(net[0].as_str(), "MAS" | "SAM") && matches!(net[1].as_str(), "MAS" | "SAM")
-            {
-                count += 1;
+/// A pattern of letters at offsets from an anchor cell, cells that aren't listed can hold anything.
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Stencil {
+    cells: Vec<(Pos, u8)>,
+}
+
+impl Stencil {
+    const DIRECTIONS: [Pos; 8] = [
+        Pos::new(1, 0),
+        Pos::new(1, 1),
+        Pos::new(0, 1),
+        Pos::new(-1, 1),
+        Pos::new(-1, 0),
+        Pos::new(-1, -1),
+        Pos::new(0, -1),
+        Pos::new(1, -1),
+    ];
+
+    /// `word` written in any of the 8 directions.
+    fn word(word: &str) -> Vec<Stencil> {
+        let stencils = Self::DIRECTIONS.into_iter().map(|dir| {
+            let cells = (0..).zip(word.bytes()).map(|(i, b)| (dir * i, b));
+            Stencil {
+                cells: cells.collect(),
             }
-            net.iter_mut().for_each(|s| s.clear())
+            .normalized()
+        });
+        Self::distinct(stencils)
+    }
+
+    /// A 2D pattern with `.` as wildcard, in all 4 rotations.
+    fn pattern(pattern: &str) -> Vec<Stencil> {
+        let cells: Vec<_> = (0..)
+            .zip(pattern.lines())
+            .flat_map(|(y, line)| {
+                (0..)
+                    .zip(line.bytes())
+                    .filter(|&(_, b)| b != b'.')
+                    .map(move |(x, b)| (Pos::new(x, y), b))
+            })
+            .collect();
+        let rotations = (0..4).map(|turns| {
+            let cells = cells
+                .iter()
+                .map(|&(pos, b)| ((0..turns).fold(pos, |pos, _| pos.perp()), b))
+                .collect();
+            Stencil { cells }.normalized()
+        });
+        Self::distinct(rotations)
+    }
+
+    /// Shifts the cells so the smallest offsets are `0`, which makes equal shapes compare equal.
+    fn normalized(mut self) -> Self {
+        let min = self
+            .cells
+            .iter()
+            .fold(Pos::MAX, |min, &(pos, _)| min.min(pos));
+        for (pos, _) in &mut self.cells {
+            *pos -= min;
         }
+        self.cells.sort_by_key(|&(pos, _)| (pos.y, pos.x));
+        self
+    }
+
+    /// Drops duplicates, so e.g. palindromes are not counted twice.
+    fn distinct(stencils: impl Iterator<Item = Stencil>) -> Vec<Stencil> {
+        stencils.fold(vec![], |mut distinct, stencil| {
+            if !distinct.contains(&stencil) {
+                distinct.push(stencil);
+            }
+            distinct
+        })
+    }
+
+    #[inline]
+    fn matches(&self, grid: &Grid, anchor: Pos) -> bool {
+        self.cells
+            .iter()
+            .all(|&(offset, b)| grid.get(anchor + offset) == Some(b))
     }
-    count
 }
 
-fn fill_net_x(matrix: &[Box<[char]>], net: &mut [String; 2], (col, row): (usize, usize)) {
-    write!(
-        &mut net[0],
-        "{}{}{}",
-        matrix[row][col],
-        matrix[row + 1][col + 1],
-        matrix[row + 2][col + 2]
-    )
-    .unwrap();
-    write!(
-        &mut net[1],
-        "{}{}{}",
-        matrix[row][col + 2],
-        matrix[row + 1][col + 1],
-        matrix[row + 2][col]
-    )
-    .unwrap();
+#[derive(Copy, Clone, Debug)]
+struct Match<'s> {
+    anchor: Pos,
+    stencil: &'s Stencil,
+}
+
+impl<'s> Match<'s> {
+    /// The grid positions of the matched letters.
+    fn positions(self) -> impl Iterator<Item = Pos> + 's {
+        let anchor = self.anchor;
+        self.stencil
+            .cells
+            .iter()
+            .map(move |&(offset, _)| anchor + offset)
+    }
 }
 
 #[cfg(test)]
@@ -143,4 +205,37 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(9));
     }
+
+    #[test]
+    fn test_match_positions() {
+        let grid = Grid::new("..X...\n.SAMX.\n.A..A.\nXMAS.S\n.X....\n");
+        let stencils = Stencil::word("XMAS");
+        let matches = grid
+            .find(&stencils)
+            .map(|m| {
+                m.positions()
+                    .map(|pos| (pos.x, pos.y))
+                    .sorted()
+                    .collect_vec()
+            })
+            .sorted()
+            .collect_vec();
+        assert_eq!(
+            matches,
+            [
+                vec![(0, 3), (1, 3), (2, 3), (3, 3)],
+                vec![(1, 1), (1, 2), (1, 3), (1, 4)],
+                vec![(1, 1), (2, 1), (3, 1), (4, 1)],
+                vec![(2, 0), (3, 1), (4, 2), (5, 3)],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stencils() {
+        assert_eq!(Stencil::word("XMAS").len(), 8);
+        assert_eq!(Stencil::word("ABA").len(), 4);
+        assert_eq!(Stencil::pattern("M.S\n.A.\nM.S").len(), 4);
+        assert_eq!(Stencil::pattern("M.M\n.A.\nM.M").len(), 1);
+    }
 }
@@ -1,6 +1,7 @@
 use itertools::Itertools;
-use std::cell::Cell;
-use std::collections::HashMap;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::fmt::{self, Display, Formatter};
 use std::str::FromStr;
 
 advent_of_code::solution!(5);
@@ -12,7 +13,13 @@ pub fn part_one(input: &str) -> Option<u32> {
 
 pub fn part_two(input: &str) -> Option<u32> {
     let (ordering_rules, print_orders) = parse_input2(input);
-    Some(correct_pages(&ordering_rules, &print_orders))
+    match correct_pages(&ordering_rules, &print_orders) {
+        Ok(corrected) => Some(corrected),
+        Err(cycle) => {
+            eprintln!("{cycle}");
+            None
+        }
+    }
 }
 
 fn sum_correct_middle_pages(ordering_rules: &[(u32, u32)], print_orders: &[Vec<u32>]) -> u32 {
@@ -43,37 +50,96 @@ fn build_check_map(ordering_rules: &[(u32, u32)]) -> HashMap<u32, Vec<u32>> {
     hm
 }
 
-fn correct_pages(ordering_rules: &[(u32, u32)], print_orders: &[Vec<u32>]) -> u32 {
+fn correct_pages(ordering_rules: &[(u32, u32)], print_orders: &[Vec<u32>]) -> Result<u32, Cycle> {
     let mut corrected = 0;
     let check = build_check_map(ordering_rules);
     for print_order in print_orders {
-        let corrected_order = print_order.iter().map(Cell::new).collect_vec();
-        if swap_correct(&check, &corrected_order) {
-            while swap_correct(&check, &corrected_order) {
-                // correcting pages
-            }
-            corrected += corrected_order[print_order.len() / 2].get();
+        let corrected_order = topological_order(&check, print_order)?;
+        if corrected_order != *print_order {
+            corrected += corrected_order[print_order.len() / 2];
         }
     }
-    corrected
+    Ok(corrected)
 }
 
-fn swap_correct(check: &HashMap<u32, Vec<u32>>, corrected_page: &Vec<Cell<&u32>>) -> bool {
-    let mut has_error = false;
-    for (idx, page) in corrected_page.iter().enumerate() {
-        if let Some(before) = check.get(page.get()) {
-            for after in before {
-                let pos = corrected_page[..idx]
-                    .iter()
-                    .find_position(|&x| x.get() == after);
-                if let Some((pos, _)) = pos {
-                    has_error = true;
-                    corrected_page[pos].swap(&corrected_page[idx]);
-                }
+/// Sorts the pages of an update with Kahn's algorithm over the rules between those pages.
+///
+/// Of all pages that may be printed next, the one coming first in the update is picked, so correct
+/// updates keep their order.
+fn topological_order(
+    check: &HashMap<u32, Vec<u32>>,
+    print_order: &[u32],
+) -> Result<Vec<u32>, Cycle> {
+    let index: HashMap<u32, usize> = print_order
+        .iter()
+        .enumerate()
+        .map(|(i, &p)| (p, i))
+        .collect();
+    let successors = |i: usize| {
+        check
+            .get(&print_order[i])
+            .into_iter()
+            .flatten()
+            .filter_map(|after| index.get(after).copied())
+    };
+
+    let mut in_degree = vec![0; print_order.len()];
+    for i in 0..print_order.len() {
+        for j in successors(i) {
+            in_degree[j] += 1;
+        }
+    }
+    let mut ready: BinaryHeap<_> = (0..print_order.len())
+        .filter(|&i| in_degree[i] == 0)
+        .map(Reverse)
+        .collect();
+    let mut sorted = Vec::with_capacity(print_order.len());
+    while let Some(Reverse(i)) = ready.pop() {
+        sorted.push(print_order[i]);
+        for j in successors(i) {
+            in_degree[j] -= 1;
+            if in_degree[j] == 0 {
+                ready.push(Reverse(j));
             }
         }
     }
-    has_error
+    if sorted.len() == print_order.len() {
+        return Ok(sorted);
+    }
+
+    // every page left over still has a left over page it must come after, so walking those
+    // predecessors has to run into a cycle.
+    let predecessor = |j: usize| {
+        (0..print_order.len())
+            .find(|&i| in_degree[i] > 0 && successors(i).any(|s| s == j))
+            .unwrap()
+    };
+    let mut walk = vec![(0..print_order.len()).find(|&i| in_degree[i] > 0).unwrap()];
+    loop {
+        let next = predecessor(*walk.last().unwrap());
+        if let Some(start) = walk.iter().position(|&i| i == next) {
+            let mut cycle = walk[start..].iter().map(|&i| print_order[i]).collect_vec();
+            cycle.push(print_order[next]);
+            cycle.reverse();
+            return Err(Cycle(cycle));
+        }
+        walk.push(next);
+    }
+}
+
+/// Pages that the ordering rules require to come before themselves, the first page is repeated at
+/// the end.
+#[derive(Debug, PartialEq, Eq)]
+struct Cycle(Vec<u32>);
+
+impl Display for Cycle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the ordering rules contain a cycle: {}",
+            self.0.iter().join(" -> ")
+        )
+    }
 }
 
 #[derive(Debug)]
@@ -140,4 +206,22 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(123));
     }
+
+    #[test]
+    fn test_topological_order() {
+        let check = build_check_map(&[(1, 2), (2, 3), (3, 1), (4, 1)]);
+        assert_eq!(topological_order(&check, &[3, 4, 1]), Ok(vec![3, 4, 1]));
+        assert_eq!(topological_order(&check, &[1, 4, 2]), Ok(vec![4, 1, 2]));
+    }
+
+    #[test]
+    fn test_cycle() {
+        let check = build_check_map(&[(1, 2), (2, 3), (3, 1), (4, 1)]);
+        let cycle = topological_order(&check, &[4, 3, 2, 1]).unwrap_err();
+        assert_eq!(cycle, Cycle(vec![3, 1, 2, 3]));
+        assert_eq!(
+            cycle.to_string(),
+            "the ordering rules contain a cycle: 3 -> 1 -> 2 -> 3"
+        );
+    }
 }
@@ -91,73 +91,114 @@ fn count_obstacles(map_size: MapSize, guard_start: Guard, obstacles: Obstacles)
         guard_walk = trace(&map_size, guard, &obstacles, guard.0, &mut path);
     }
 
+    let jumps = JumpTable::new(map_size, &obstacles);
+    let states = (map_size.0 * map_size.1) as usize * 4;
     path.par_iter()
         .filter(|&&pos| pos != guard_start.0)
-        .filter(|&&pos| simulate_walk_with_obstacle(map_size, guard_start, &obstacles, pos))
+        .map_init(
+            || vec![0u64; states.div_ceil(64)],
+            |seen, &pos| simulate_walk_with_obstacle(&jumps, guard_start, pos, seen),
+        )
+        .filter(|&is_loop| is_loop)
         .count() as u32
 }
 
+/// Jumps from turn to turn, returning whether the guard ends up in a loop.
+///
+/// `seen` is a bitset over all guard states (`pos * 4 + direction`), reused between walks.
 #[inline]
 fn simulate_walk_with_obstacle(
-    map_size: MapSize,
+    jumps: &JumpTable,
     guard_start: Guard,
-    obstacles: &Obstacles,
     additional_obstacle: Pos2,
+    seen: &mut [u64],
 ) -> bool {
-    let mut guard_walk = Some(guard_start);
-    let mut loop_check = HashSet::with_capacity_and_hasher(obstacles.len() / 8, Default::default());
+    seen.fill(0);
+    let mut guard_walk = jumps.jump(guard_start, additional_obstacle);
     while let Some(guard) = guard_walk {
-        if loop_check.contains(&guard) {
+        let state = guard.0 as usize * 4 + guard.1 as usize;
+        let bit = 1 << (state % 64);
+        if seen[state / 64] & bit != 0 {
             return true;
         }
-        loop_check.insert(guard);
-        guard_walk = trace_no_track(&map_size, guard, obstacles, additional_obstacle);
+        seen[state / 64] |= bit;
+        guard_walk = jumps.jump(guard, additional_obstacle);
     }
     false
 }
 
-#[inline]
-fn trace(
-    map_size: &MapSize,
-    guard: Guard,
-    obstacles: &Obstacles,
-    additional_obstacle: Pos2,
-    path: &mut HashSet<Pos2>,
-) -> Option<Guard> {
-    let up_limit = 0;
-    let down_limit = map_size.1 - 1;
-    let left_limit = 0;
-    let right_limit = map_size.0 - 1;
-
-    let (mut curr_x, mut curr_y) = map_size.to_coord(guard.0);
-    let curr_dir = guard.1;
-
-    loop {
-        path.insert(map_size.to_pos((curr_x, curr_y)));
+/// For each cell and direction, the cell in front of the next obstacle, or `None` if the guard
+/// walks off the map.
+struct JumpTable {
+    map_size: MapSize,
+    next: Vec<[Option<Pos2>; 4]>,
+}
 
-        match (curr_dir, curr_x, curr_y) {
-            (Direction::Up, _, up) if up == up_limit => return None,
-            (Direction::Down, _, down) if down == down_limit => return None,
-            (Direction::Left, left, _) if left == left_limit => return None,
-            (Direction::Right, right, _) if right == right_limit => return None,
-            _ => (),
+impl JumpTable {
+    fn new(map_size: MapSize, obstacles: &Obstacles) -> Self {
+        let cells = map_size.0 * map_size.1;
+        let mut next = vec![[None; 4]; cells as usize];
+        for dir in [
+            Direction::Up,
+            Direction::Right,
+            Direction::Down,
+            Direction::Left,
+        ] {
+            // visit the cell ahead before the cell itself.
+            let order: Box<dyn Iterator<Item = Pos2>> = match dir {
+                Direction::Up | Direction::Left => Box::new(0..cells),
+                Direction::Down | Direction::Right => Box::new((0..cells).rev()),
+            };
+            for pos in order {
+                let ahead = dir.checked_offset(&map_size, map_size.to_coord(pos));
+                next[pos as usize][dir as usize] = ahead.and_then(|ahead| {
+                    let ahead = map_size.to_pos(ahead);
+                    if obstacles.contains(&ahead) {
+                        Some(pos)
+                    } else {
+                        next[ahead as usize][dir as usize]
+                    }
+                });
+            }
         }
+        JumpTable { map_size, next }
+    }
 
-        let (new_x, new_y) = curr_dir.offset_calc((curr_x, curr_y));
-        let new_pos = map_size.to_pos((new_x, new_y));
-        if additional_obstacle == new_pos || obstacles.contains(&new_pos) {
-            return Some((map_size.to_pos((curr_x, curr_y)), curr_dir.turn()));
+    /// Walks up to the next obstacle, taking `additional_obstacle` into account, and turns.
+    #[inline]
+    fn jump(&self, (pos, dir): Guard, additional_obstacle: Pos2) -> Option<Guard> {
+        let natural = self.next[pos as usize][dir as usize];
+        let (x, y) = self.map_size.to_coord(pos);
+        let (ox, oy) = self.map_size.to_coord(additional_obstacle);
+        let (sx, sy) = match natural {
+            Some(stop) => self.map_size.to_coord(stop),
+            None => match dir {
+                Direction::Up => (x, 0),
+                Direction::Down => (x, self.map_size.1 - 1),
+                Direction::Left => (0, y),
+                Direction::Right => (self.map_size.0 - 1, y),
+            },
+        };
+        let blocked = match dir {
+            Direction::Up => (ox == x && sy <= oy && oy < y).then(|| (x, oy + 1)),
+            Direction::Down => (ox == x && y < oy && oy <= sy).then(|| (x, oy - 1)),
+            Direction::Left => (oy == y && sx <= ox && ox < x).then(|| (ox + 1, y)),
+            Direction::Right => (oy == y && x < ox && ox <= sx).then(|| (ox - 1, y)),
+        };
+        match blocked {
+            Some(stop) => Some((self.map_size.to_pos(stop), dir.turn())),
+            None => natural.map(|stop| (stop, dir.turn())),
         }
-        (curr_x, curr_y) = (new_x, new_y);
     }
 }
 
 #[inline]
-fn trace_no_track(
+fn trace(
     map_size: &MapSize,
     guard: Guard,
     obstacles: &Obstacles,
     additional_obstacle: Pos2,
+    path: &mut HashSet<Pos2>,
 ) -> Option<Guard> {
     let up_limit = 0;
     let down_limit = map_size.1 - 1;
@@ -168,6 +209,8 @@ fn trace_no_track(
     let curr_dir = guard.1;
 
     loop {
+        path.insert(map_size.to_pos((curr_x, curr_y)));
+
         match (curr_dir, curr_x, curr_y) {
             (Direction::Up, _, up) if up == up_limit => return None,
             (Direction::Down, _, down) if down == down_limit => return None,
@@ -204,6 +247,17 @@ impl Direction {
         }
     }
 
+    /// Like [`Direction::offset_calc`], but `None` when leaving the map.
+    #[inline]
+    fn checked_offset(self, map_size: &MapSize, (x, y): Pos) -> Option<Pos> {
+        match self {
+            Direction::Up => Some((x, y.checked_sub(1)?)),
+            Direction::Down => (y + 1 < map_size.1).then_some((x, y + 1)),
+            Direction::Left => Some((x.checked_sub(1)?, y)),
+            Direction::Right => (x + 1 < map_size.0).then_some((x + 1, y)),
+        }
+    }
+
     #[inline]
     fn offset_calc(self, (x, y): Pos) -> Pos {
         match self {
@@ -258,4 +312,32 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(6));
     }
+
+    #[test]
+    fn test_jump_table() {
+        let (map_size, guard, obstacles) =
+            parse_input(&advent_of_code::template::read_file("examples", DAY));
+        let jumps = JumpTable::new(map_size, &obstacles);
+        // the starting cell is never ahead of the guard, so it doesn't block anything.
+        let no_obstacle = guard.0;
+        let first = jumps.jump(guard, no_obstacle);
+        assert_eq!(first, Some((map_size.to_pos((4, 1)), Direction::Right)));
+        let blocked = jumps.jump(guard, map_size.to_pos((4, 3)));
+        assert_eq!(blocked, Some((map_size.to_pos((4, 4)), Direction::Right)));
+        let leaving = jumps.jump((map_size.to_pos((7, 7)), Direction::Down), no_obstacle);
+        assert_eq!(leaving, None);
+    }
+
+    #[test]
+    fn test_loop_detection() {
+        let (map_size, guard, obstacles) =
+            parse_input(&advent_of_code::template::read_file("examples", DAY));
+        let jumps = JumpTable::new(map_size, &obstacles);
+        let mut seen = vec![0; (map_size.0 * map_size.1 * 4).div_ceil(64) as usize];
+        let mut is_loop =
+            |pos| simulate_walk_with_obstacle(&jumps, guard, map_size.to_pos(pos), &mut seen);
+        assert!(is_loop((3, 6)));
+        assert!(is_loop((7, 9)));
+        assert!(!is_loop((4, 3)));
+    }
 }
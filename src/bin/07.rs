@@ -2,117 +2,188 @@
 
 use anyhow::{bail, Context};
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
-use std::cmp::Ordering;
-use std::fmt::{Debug, Formatter};
+use std::fmt::{Display, Formatter};
 use std::ops::{Add, Mul};
 
-advent_of_code::solution!(7);
+advent_of_code::solution!(7, modes: { "--explain" => explain });
 
 pub fn part_one(input: &str) -> Option<u64> {
     let equations = parse_input(input).unwrap();
-    Some(count_solvable(equations, calculate_2))
+    Some(count_solvable(equations, PART_ONE))
 }
 
 pub fn part_two(input: &str) -> Option<u64> {
     let equations = parse_input(input).unwrap();
-    Some(count_solvable(equations, calculate_3))
+    Some(count_solvable(equations, PART_TWO))
 }
 
-fn concat(first: u64, second: u64) -> u64 {
-    first * (10u64.pow(second.ilog10() + 1)) + second
+/// Prints the satisfying formula of every calibrated equation, e.g. `cargo solve 7 -- --explain`.
+///
+/// The operators default to those of part two and can be chosen with `--operators "+,*"`.
+fn explain(input: &str) {
+    let args: Vec<String> = std::env::args().collect();
+    let operators = advent_of_code::template::flag_value::<String>(&args, "--operators")
+        .map_err(anyhow::Error::msg)
+        .and_then(|symbols| match symbols {
+            Some(symbols) => parse_operators(&symbols),
+            None => Ok(PART_TWO.to_vec()),
+        });
+    let operators = match operators {
+        Ok(operators) => operators,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(advent_of_code::template::EXIT_FAILURE);
+        }
+    };
+
+    let equations = parse_input(input).unwrap();
+    let mut total = 0;
+    for equation in &equations {
+        if let Some(used) = solve(equation.total, &equation.candidates, &operators) {
+            let formula = Formula(equation, &used);
+            debug_assert_eq!(formula.evaluate(), equation.total);
+            println!("{}: {formula}", equation.total);
+            total += equation.total;
+        }
+    }
+    println!("total calibration result: {total}");
 }
 
-fn count_solvable(equations: Vec<Equation>, rec_fn: fn(u64, u64, &[u64]) -> bool) -> u64 {
-    equations
-        .par_iter()
-        .filter(|equation| rec_fn(equation.total, 0, &equation.candidates))
-        .map(|equation| equation.total)
-        .sum()
+/// Parses a comma separated list of operator symbols, e.g. `+,*`.
+fn parse_operators(symbols: &str) -> anyhow::Result<Vec<Operator>> {
+    symbols
+        .split(',')
+        .map(|symbol| {
+            OPERATORS
+                .iter()
+                .find(|op| op.symbol == symbol.trim())
+                .copied()
+                .with_context(|| format!("unknown operator '{symbol}'"))
+        })
+        .collect()
 }
 
-fn calculate_2(limit: u64, curr_sum: u64, nums: &[u64]) -> bool {
-    match curr_sum.cmp(&limit) {
-        Ordering::Less => {}
-        Ordering::Equal => return true,
-        Ordering::Greater => return false,
+fn concat(first: u64, second: u64) -> u64 {
+    first * (10u64.pow(second.checked_ilog10().unwrap_or(0) + 1)) + second
+}
+
+/// Undoes [`concat`]: the number `target` started with if it ends with the digits of `second`.
+fn strip_suffix(target: u64, second: u64) -> Operands {
+    let shift = 10u64.pow(second.checked_ilog10().unwrap_or(0) + 1);
+    match target.checked_sub(second) {
+        Some(rest) if rest % shift == 0 => Operands::One(rest / shift),
+        _ => Operands::None,
     }
-    let Some(&num) = nums.first() else {
-        return false;
-    };
-    calculate_2(limit, curr_sum + num, &nums[1..]) || calculate_2(limit, curr_sum * num, &nums[1..])
 }
 
-fn calculate_3(limit: u64, curr_sum: u64, nums: &[u64]) -> bool {
-    match curr_sum.cmp(&limit) {
-        Ordering::Less => {}
-        Ordering::Equal => return true,
-        Ordering::Greater => return false,
+/// Undoes a multiplication, where any left operand turns into `0` when multiplied by `0`.
+fn divide(target: u64, second: u64) -> Operands {
+    match (target, second) {
+        (0, 0) => Operands::Any,
+        (_, 0) => Operands::None,
+        _ if target % second == 0 => Operands::One(target / second),
+        _ => Operands::None,
     }
-    let Some(&num) = nums.first() else {
-        return false;
-    };
-    calculate_3(limit, curr_sum + num, &nums[1..])
-        || calculate_3(limit, curr_sum * num, &nums[1..])
-        || calculate_3(limit, concat(curr_sum, num), &nums[1..])
 }
 
-#[allow(unused)]
-fn dyn_count_solvable<const N: usize>(
-    equations: Vec<Equation>,
-    operators: [fn(u64, u64) -> u64; N],
-) -> u64 {
+/// The left operands for which an [`Operator`] results in a given number.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Operands {
+    None,
+    One(u64),
+    Any,
+}
+
+/// A binary operator together with its inverse, which recovers the left operand from a result and
+/// the right operand.
+#[derive(Copy, Clone)]
+struct Operator {
+    symbol: &'static str,
+    apply: fn(u64, u64) -> u64,
+    unapply: fn(u64, u64) -> Operands,
+}
+
+const ADD: Operator = Operator {
+    symbol: "+",
+    apply: u64::add,
+    unapply: |target, second| {
+        target
+            .checked_sub(second)
+            .map_or(Operands::None, Operands::One)
+    },
+};
+const MUL: Operator = Operator {
+    symbol: "*",
+    apply: u64::mul,
+    unapply: divide,
+};
+const CONCAT: Operator = Operator {
+    symbol: "||",
+    apply: concat,
+    unapply: strip_suffix,
+};
+
+/// All known operators, an operator set may use any of them.
+const OPERATORS: [Operator; 3] = [ADD, MUL, CONCAT];
+const PART_ONE: &[Operator] = &[ADD, MUL];
+const PART_TWO: &[Operator] = &[ADD, MUL, CONCAT];
+
+fn count_solvable(equations: Vec<Equation>, operators: &[Operator]) -> u64 {
     equations
-        .into_iter()
-        .filter(|equation| dyn_calculate(equation.total, 0, &equation.candidates, operators))
+        .par_iter()
+        .filter(|equation| solve(equation.total, &equation.candidates, operators).is_some())
         .map(|equation| equation.total)
         .sum()
 }
 
-#[allow(unused)]
-fn dyn_calculate<const N: usize>(
-    limit: u64,
-    curr_sum: u64,
-    nums: &[u64],
-    operators: [fn(u64, u64) -> u64; N],
-) -> bool {
-    match curr_sum.cmp(&limit) {
-        Ordering::Less => {}
-        Ordering::Equal => return true,
-        Ordering::Greater => return false,
-    }
-    let Some(&num) = nums.first() else {
-        return false;
-    };
-    operators
-        .iter()
-        .any(|op| dyn_calculate(limit, op(curr_sum, num), &nums[1..], operators))
+/// Solves the equation backwards: the last operator has to turn the remaining numbers into
+/// `target`, so it's peeled off with its inverse, which prunes most operators right away (a product
+/// has to divide the target, a concatenation has to be a suffix of it).
+///
+/// Returns the operators to put between the numbers, from left to right.
+fn solve(target: u64, nums: &[u64], operators: &[Operator]) -> Option<Vec<Operator>> {
+    let (&last, rest) = nums.split_last()?;
+    if rest.is_empty() {
+        return (target == last).then(Vec::new);
+    }
+    operators.iter().find_map(|&op| {
+        let mut used = match (op.unapply)(target, last) {
+            Operands::None => return None,
+            Operands::One(target) => solve(target, rest, operators)?,
+            // whatever the remaining numbers evaluate to, so any operators do.
+            Operands::Any => vec![*operators.first()?; rest.len() - 1],
+        };
+        used.push(op);
+        Some(used)
+    })
 }
 
-#[allow(dead_code)]
-struct FormatFormula<'a>(&'a Equation, &'a [fn(u64, u64) -> u64]);
+struct Formula<'a>(&'a Equation, &'a [Operator]);
 
-impl Debug for FormatFormula<'_> {
+impl Display for Formula<'_> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let Self(equation, fun_candidates) = self;
+        let Self(equation, operators) = self;
         let mut start = equation.candidates.iter();
         write!(f, "{}", start.next().unwrap())?;
-        // print.push_str(&format!("{}", start.next().unwrap()));
-        for (fun, num) in fun_candidates.iter().zip(start) {
-            let fun = if fun == &(u64::add as fn(u64, u64) -> u64) {
-                "+"
-            } else if fun == &(u64::mul as fn(u64, u64) -> u64) {
-                "*"
-            } else if fun == &(concat as fn(u64, u64) -> u64) {
-                "||"
-            } else {
-                panic!("unknown function");
-            };
-            write!(f, " {} {}", fun, num)?;
+        for (op, num) in operators.iter().zip(start) {
+            write!(f, " {} {}", op.symbol, num)?;
         }
         Ok(())
     }
 }
 
+impl Formula<'_> {
+    /// Evaluates the formula left to right.
+    fn evaluate(&self) -> u64 {
+        let Self(equation, operators) = self;
+        let (&first, rest) = equation.candidates.split_first().unwrap();
+        operators
+            .iter()
+            .zip(rest)
+            .fold(first, |acc, (op, &num)| (op.apply)(acc, num))
+    }
+}
+
 #[derive(Debug)]
 struct Equation {
     total: u64,
@@ -143,6 +214,8 @@ fn parse_input(input: &str) -> anyhow::Result<Vec<Equation>> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use itertools::Itertools;
+    use rstest::rstest;
 
     #[test]
     fn test_part_one() {
@@ -162,21 +235,95 @@ mod tests {
             total: 123,
             candidates: vec![55, 14, 34, 99, 12],
         };
-        let ff = FormatFormula(&equation, &[u64::add, u64::mul, concat, u64::add]);
-        assert_eq!("55 + 14 * 34 || 99 + 12", format!("{ff:?}"));
+        let formula = Formula(&equation, &[ADD, MUL, CONCAT, ADD]);
+        assert_eq!("55 + 14 * 34 || 99 + 12", formula.to_string());
+        assert_eq!(formula.evaluate(), concat((55 + 14) * 34, 99) + 12);
+    }
+
+    #[rstest]
+    #[case(190, &[10, 19], PART_ONE, Some("10 * 19"))]
+    #[case(3267, &[81, 40, 27], PART_ONE, Some("81 * 40 + 27"))]
+    #[case(156, &[15, 6], PART_ONE, None)]
+    #[case(156, &[15, 6], PART_TWO, Some("15 || 6"))]
+    #[case(7290, &[6, 8, 6, 15], PART_TWO, Some("6 * 8 || 6 * 15"))]
+    #[case(192, &[17, 8, 14], PART_TWO, Some("17 || 8 + 14"))]
+    #[case(20, &[10, 10, 10], PART_TWO, None)]
+    fn test_solve(
+        #[case] total: u64,
+        #[case] candidates: &[u64],
+        #[case] operators: &[Operator],
+        #[case] expected: Option<&str>,
+    ) {
+        let equation = Equation {
+            total,
+            candidates: candidates.to_vec(),
+        };
+        let used = solve(total, candidates, operators);
+        let formula = used.as_ref().map(|used| Formula(&equation, used));
+        assert_eq!(
+            formula.as_ref().map(ToString::to_string).as_deref(),
+            expected
+        );
+        assert!(formula.is_none_or(|formula| formula.evaluate() == total));
     }
 
     #[test]
-    fn test_part_one_alternative() {
-        let equations = parse_input(&advent_of_code::template::read_file("examples", DAY)).unwrap();
-        let result = dyn_count_solvable(equations, [u64::add, u64::mul]);
-        assert_eq!(result, 3749);
+    fn test_parse_operators() {
+        let symbols = |ops: Vec<Operator>| ops.iter().map(|op| op.symbol).collect::<Vec<_>>();
+        assert_eq!(symbols(parse_operators("+, ||").unwrap()), ["+", "||"]);
+        assert!(parse_operators("+,-").is_err());
     }
 
     #[test]
-    fn test_part_two_alternative() {
-        let equations = parse_input(&advent_of_code::template::read_file("examples", DAY)).unwrap();
-        let result = dyn_count_solvable(equations, [u64::add, u64::mul, concat]);
-        assert_eq!(result, 11387);
+    fn test_strip_suffix() {
+        assert_eq!(strip_suffix(12345, 345), Operands::One(12));
+        assert_eq!(strip_suffix(12345, 45), Operands::One(123));
+        assert_eq!(strip_suffix(12345, 44), Operands::None);
+        assert_eq!(strip_suffix(45, 345), Operands::None);
+        assert_eq!(strip_suffix(120, 0), Operands::One(12));
+    }
+
+    #[test]
+    fn test_zero_operands() {
+        assert_eq!(concat(12, 0), 120);
+        assert_eq!(concat(0, 0), 0);
+        assert_eq!(divide(0, 0), Operands::Any);
+        assert_eq!(divide(5, 0), Operands::None);
+        assert_eq!(divide(0, 5), Operands::One(0));
+    }
+
+    /// Whether any combination of operators, evaluated left to right, results in the total.
+    fn solvable_forwards(equation: &Equation, operators: &[Operator]) -> bool {
+        std::iter::repeat_n(operators.iter().copied(), equation.candidates.len() - 1)
+            .multi_cartesian_product()
+            .any(|used| Formula(equation, &used).evaluate() == equation.total)
+    }
+
+    #[rstest]
+    #[case(PART_ONE, 3749)]
+    #[case(PART_TWO, 11387)]
+    fn test_solve_matches_forwards(#[case] operators: &[Operator], #[case] expected: u64) {
+        let example = advent_of_code::template::read_file("examples", DAY);
+        let equations = parse_input(&example).unwrap();
+        let forwards = equations
+            .iter()
+            .filter(|equation| solvable_forwards(equation, operators))
+            .map(|equation| equation.total)
+            .sum::<u64>();
+        assert_eq!(forwards, expected);
+        assert_eq!(count_solvable(equations, operators), expected);
+
+        let zeros = "0: 5 0\n0: 0 0 3\n0: 3 4 0 2\n10: 0 10\n50: 5 0\n7: 7 0 0\n0: 1 1\n20: 2 0 0";
+        for equation in parse_input(zeros).unwrap() {
+            let used = solve(equation.total, &equation.candidates, operators);
+            assert_eq!(
+                used.is_some(),
+                solvable_forwards(&equation, operators),
+                "{equation:?}"
+            );
+            if let Some(used) = used {
+                assert_eq!(Formula(&equation, &used).evaluate(), equation.total);
+            }
+        }
     }
 }
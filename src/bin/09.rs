@@ -1,106 +1,55 @@
-use rustc_hash::FxHashMap;
+use itertools::Itertools;
 use std::cell::Cell;
-use std::collections::VecDeque;
-use std::iter::{Enumerate, Peekable, Rev};
-use std::slice::Iter;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
 
 advent_of_code::solution!(9);
 
 pub fn part_one(input: &str) -> Option<u64> {
-    let (ids, _, _) = parse_input(input);
+    let ids = parse_blocks(input);
     Some(defrag(ids))
 }
 
 pub fn part_two(input: &str) -> Option<u64> {
-    let (ids, _, _) = parse_input(input);
-    Some(defrag_2(ids))
+    Some(defrag_2(parse_files(input)))
 }
 
-fn defrag_2(ids: Ids) -> u64 {
-    let mut back = ids.iter().enumerate().rev().peekable();
-    'outer: loop {
-        let Some((to, from, _file_id)) = find_block_back(&mut back) else {
-            break 'outer;
-        };
-        let size = (from..=to).count();
-        let mut front = ids.iter().enumerate();
-        let (f_from, f_to) = 'find_candidate: loop {
-            let Some((f_from, f_to)) = find_free_range_forward(&mut front) else {
-                continue 'outer;
-            };
-            let slot = (f_from..f_to).count();
-            if slot < size {
-                continue 'find_candidate;
-            }
-            if f_from >= to {
-                continue 'outer;
-            }
-            break 'find_candidate (f_from, f_to);
-        };
-        for (back, front) in (from..=to).zip(f_from..f_to) {
-            let back = &ids[back];
-            let front = &ids[front];
-            debug_assert!(back.get().is_some());
-            debug_assert!(front.get().is_none());
-            back.swap(front);
-        }
-    }
-    ids.into_iter()
-        .enumerate()
-        .map(|(idx, file_id)| match file_id.get() {
-            Some(file_id) => idx as u64 * file_id as u64,
-            None => 0,
-        })
-        .sum()
-}
-
-fn find_free_range_forward(
-    iter: &mut Enumerate<Iter<Cell<Option<FileId>>>>,
-) -> Option<(usize, usize)> {
-    let mut idx_from = None;
-    #[allow(clippy::while_let_on_iterator)]
-    while let Some((idx, inner)) = iter.next() {
-        if idx_from.is_none() && inner.get().is_some() {
-            continue;
-        }
-        if inner.get().is_none() {
-            if idx_from.is_none() {
-                idx_from = Some(idx);
-            }
-        } else {
-            return Some((idx_from.unwrap(), idx));
+/// Moves every file, highest id first, into the leftmost gap that fits it.
+///
+/// Gaps are kept in one min-heap of start positions per gap size, so the leftmost fitting gap is
+/// the smallest head of the heaps for sizes `size..=9`. Space freed by a moved file never has to be
+/// tracked: every file that is still to be moved lies left of it.
+fn defrag_2(files: FileMapOrd) -> u64 {
+    let mut gaps: [BinaryHeap<Reverse<u32>>; 10] = Default::default();
+    for ((_, (_, end)), (_, (start, _))) in files.iter().tuple_windows() {
+        let size = (start - end) as usize;
+        if size > 0 {
+            gaps[size].push(Reverse(*end));
         }
     }
-    None
-}
 
-#[allow(clippy::type_complexity)]
-fn find_block_back(
-    iter: &mut Peekable<Rev<Enumerate<Iter<Cell<Option<FileId>>>>>>,
-) -> Option<(usize, usize, FileId)> {
-    let mut chr = None;
-    let mut idx_from = None;
-    let mut idx_to = None;
-    loop {
-        if let Some((idx, inner)) = iter.peek() {
-            if let Some(c) = chr {
-                if let Some(inner) = inner.get() {
-                    if c == inner {
-                        idx_to = Some(*idx);
-                    } else {
-                        return Some((idx_from.unwrap(), idx_to.unwrap(), c));
-                    }
+    let mut checksum = 0;
+    for &(file_id, (start, end)) in files.iter().rev() {
+        let size = end - start;
+        let gap = (size as usize..gaps.len())
+            .filter_map(|gap_size| Some((gaps[gap_size].peek()?.0, gap_size)))
+            .min();
+        let start = match gap {
+            Some((gap_start, gap_size)) if size > 0 && gap_start < start => {
+                gaps[gap_size].pop();
+                let rest = gap_size - size as usize;
+                if rest > 0 {
+                    gaps[rest].push(Reverse(gap_start + size));
                 }
-            } else if let Some(inner) = inner.get() {
-                chr = Some(inner);
-                idx_from = Some(*idx);
-                continue;
+                gap_start
             }
-            let _ = iter.next();
-        } else {
-            return None;
-        }
+            _ => start,
+        };
+        checksum += (start..start + size)
+            .map(|idx| idx as u64 * file_id as u64)
+            .sum::<u64>();
     }
+    checksum
 }
 
 fn defrag(ids: Ids) -> u64 {
@@ -136,41 +85,41 @@ fn defrag(ids: Ids) -> u64 {
 
 type FileId = u16;
 type Ids = Vec<Cell<Option<FileId>>>;
-type FileMap = FxHashMap<FileId, (u32, u32)>;
 type FileMapOrd = VecDeque<(FileId, (u32, u32))>;
 
-fn parse_input(input: &str) -> (Ids, FileMap, FileMapOrd) {
-    let mut ids = Ids::with_capacity(u16::MAX as usize);
-    let mut file_map = FileMap::default();
-    let mut file_map_ord = FileMapOrd::default();
-    let mut is_file = true;
+/// Spans `(start, end)` of the disk map, alternating between files (with their id) and gaps.
+fn parse_spans(input: &str) -> impl Iterator<Item = (Option<FileId>, (u32, u32))> + '_ {
     let mut current = 0;
-    let mut file_id = 0;
-    for line in input.lines() {
-        for num in line.chars() {
+    input
+        .lines()
+        .flat_map(|line| line.chars())
+        .zip((0..).map(|i| (i % 2 == 0).then_some(i / 2)))
+        .map(move |(num, id)| {
             let num = num.to_digit(10).unwrap();
-            let id = if is_file {
-                let x = Some(file_id);
-                file_map.insert(file_id, (current, (current + num)));
-                file_map_ord.push_back((file_id, (current, (current + num))));
-                file_id += 1;
-                x
-            } else {
-                None
-            };
-            for i in current..(current + num) {
-                ids.insert(i as _, Cell::new(id));
-            }
+            let span = (current, current + num);
             current += num;
-            is_file = !is_file;
-        }
-    }
-    (ids, file_map, file_map_ord)
+            (id, span)
+        })
+}
+
+/// Expands the disk map into one cell per block.
+fn parse_blocks(input: &str) -> Ids {
+    parse_spans(input)
+        .flat_map(|(id, (start, end))| (start..end).map(move |_| Cell::new(id)))
+        .collect()
+}
+
+/// Only the file spans of the disk map, in order.
+fn parse_files(input: &str) -> FileMapOrd {
+    parse_spans(input)
+        .filter_map(|(id, span)| Some((id?, span)))
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rstest::rstest;
 
     #[test]
     fn test_part_one() {
@@ -183,4 +132,12 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(2858));
     }
+
+    #[rstest]
+    #[case("12345", 132)]
+    #[case("1313165", 169)]
+    #[case("10101", 5)]
+    fn test_defrag_2(#[case] input: &str, #[case] expected: u64) {
+        assert_eq!(defrag_2(parse_files(input)), expected);
+    }
 }
@@ -1,22 +1,69 @@
+use advent_of_code::template::{flag_value, runner::run_part};
 use regex::Regex;
 
-advent_of_code::solution!(13);
+advent_of_code::solution!(13, modes: {
+    "--cost-a" => solve_with_pricing,
+    "--cost-b" => solve_with_pricing,
+    "--offset" => solve_with_pricing,
+});
 
 pub fn part_one(input: &str) -> Option<Cost> {
-    let config = parse_input(input);
-    Some(calculate_price(config))
+    solve(input, Pricing::DEFAULT, 0)
 }
 
 pub fn part_two(input: &str) -> Option<Cost> {
+    solve(input, Pricing::DEFAULT, Pricing::DEFAULT.offset)
+}
+
+fn solve(input: &str, pricing: Pricing, offset: i64) -> Option<Cost> {
     let mut config = parse_input(input);
-    config.iter_mut().for_each(increase_price);
-    Some(calculate_price(config))
+    config.iter_mut().for_each(|c| increase_price(c, offset));
+    Some(calculate_price(config, pricing.cost_a, pricing.cost_b))
+}
+
+/// Solves both parts with other button costs or prize offset of part two, e.g.
+/// `cargo solve 13 -- --cost-a 1 --cost-b 3 --offset 0`. Flags that are left out keep their default.
+fn solve_with_pricing(input: &str) {
+    let args: Vec<String> = std::env::args().collect();
+    let pricing = match Pricing::from_args(&args) {
+        Ok(pricing) => pricing,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(advent_of_code::template::EXIT_FAILURE);
+        }
+    };
+    run_part(|input| solve(input, pricing, 0), input, DAY, 1);
+    run_part(|input| solve(input, pricing, pricing.offset), input, DAY, 2);
+}
+
+/// Token costs of a button press and the offset added to every prize in part two.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+struct Pricing {
+    cost_a: Cost,
+    cost_b: Cost,
+    offset: i64,
+}
+
+impl Pricing {
+    const DEFAULT: Pricing = Pricing {
+        cost_a: 3,
+        cost_b: 1,
+        offset: 10_000_000_000_000,
+    };
+
+    fn from_args(args: &[String]) -> Result<Self, String> {
+        let defaults = Self::DEFAULT;
+        Ok(Pricing {
+            cost_a: flag_value(args, "--cost-a")?.unwrap_or(defaults.cost_a),
+            cost_b: flag_value(args, "--cost-b")?.unwrap_or(defaults.cost_b),
+            offset: flag_value(args, "--offset")?.unwrap_or(defaults.offset),
+        })
+    }
 }
 
-fn increase_price(c: &mut Configuration) {
-    const EXTRA: i64 = 10_000_000_000_000;
-    c.price.x += EXTRA;
-    c.price.y += EXTRA;
+fn increase_price(c: &mut Configuration, offset: i64) {
+    c.price.x += offset;
+    c.price.y += offset;
 }
 
 type Pos = glam::i64::I64Vec2;
@@ -29,26 +76,101 @@ struct Configuration {
     price: Pos,
 }
 
-fn calculate_price(configs: Vec<Configuration>) -> Cost {
+fn calculate_price(configs: Vec<Configuration>, cost_a: Cost, cost_b: Cost) -> Cost {
     configs
         .iter()
-        .map(|config| calculate_config(config, 3, 1))
+        .filter_map(|config| calculate_config(config, cost_a, cost_b))
         .sum()
 }
 
-fn calculate_config(config: &Configuration, cost_a: Cost, cost_b: Cost) -> Cost {
+/// The cheapest way to win the prize, or `None` if it can't be reached.
+fn calculate_config(config: &Configuration, cost_a: Cost, cost_b: Cost) -> Option<Cost> {
     let perp_a_b = config.a.perp_dot(config.b);
+    if perp_a_b == 0 {
+        return calculate_collinear(config, cost_a, cost_b);
+    }
+    // Cramer's rule, the solution is unique.
     let perp_a_price = config.a.perp_dot(config.price);
     let perp_price_b = config.price.perp_dot(config.b);
-    if perp_a_b == 0 || perp_a_price % perp_a_b != 0 || perp_price_b % perp_a_b != 0 {
+    if perp_a_price % perp_a_b != 0 || perp_price_b % perp_a_b != 0 {
+        return None;
+    }
+    let num_press_a = Cost::try_from(perp_price_b / perp_a_b).ok()?;
+    let num_press_b = Cost::try_from(perp_a_price / perp_a_b).ok()?;
+    Some((num_press_a * cost_a) + (num_press_b * cost_b))
+}
+
+/// Both buttons move the claw along the same line, so the prize has to lie on it as well and the
+/// problem reduces to `a * n + b * m = price` along a single axis, with many possible solutions.
+fn calculate_collinear(config: &Configuration, cost_a: Cost, cost_b: Cost) -> Option<Cost> {
+    if config.a.perp_dot(config.price) != 0 || config.b.perp_dot(config.price) != 0 {
+        return None;
+    }
+    let axis = if config.a.x != 0 || config.b.x != 0 {
         0
     } else {
-        let num_press_a = (perp_price_b / perp_a_b) as Cost;
-        let num_press_b = (perp_a_price / perp_a_b) as Cost;
-        (num_press_a * cost_a) + (num_press_b * cost_b)
+        1
+    };
+    let (a, b, price) = (
+        config.a[axis] as i128,
+        config.b[axis] as i128,
+        config.price[axis] as i128,
+    );
+    let (cost_a, cost_b) = (cost_a as i128, cost_b as i128);
+
+    let (n, m) = match (a, b) {
+        (0, 0) => (price == 0).then_some((0, 0))?,
+        (a, 0) => (price % a == 0).then_some((price / a, 0))?,
+        (0, b) => (price % b == 0).then_some((0, price / b))?,
+        (a, b) => {
+            let (g, x, y) = extended_gcd(a, b);
+            if price % g != 0 {
+                return None;
+            }
+            // all solutions are (n0 + k * step_n, m0 - k * step_m)
+            let (n0, m0) = (x * (price / g), y * (price / g));
+            let (step_n, step_m) = (b / g, a / g);
+            let k_min = div_ceil(-n0, step_n);
+            let k_max = div_floor(m0, step_m);
+            if k_min > k_max {
+                return None;
+            }
+            // the cost is linear in k, so the cheapest solution is at either end.
+            [k_min, k_max]
+                .into_iter()
+                .map(|k| (n0 + k * step_n, m0 - k * step_m))
+                .min_by_key(|&(n, m)| n * cost_a + m * cost_b)?
+        }
+    };
+    if n < 0 || m < 0 || config.a * n as i64 + config.b * m as i64 != config.price {
+        return None;
+    }
+    Cost::try_from(n * cost_a + m * cost_b).ok()
+}
+
+/// Returns `(g, x, y)` with `a * x + b * y = g = gcd(a, b)`.
+fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        (a.abs(), a.signum(), 0)
+    } else {
+        let (g, x, y) = extended_gcd(b, a.rem_euclid(b));
+        (g, y, x - a.div_euclid(b) * y)
+    }
+}
+
+fn div_floor(a: i128, b: i128) -> i128 {
+    let d = a / b;
+    if (a % b != 0) && ((a < 0) != (b < 0)) {
+        d - 1
+    } else {
+        d
     }
 }
 
+fn div_ceil(a: i128, b: i128) -> i128 {
+    -div_floor(-a, b)
+}
+
 fn parse_input(input: &str) -> Vec<Configuration> {
     let mut configs = vec![];
     let regex = Regex::new(r#"Button\s+A:\s+X\+(?<ax>\d+),\s+Y\+(?<ay>\d+)\nButton\s+B:\s+X\+(?<bx>\d+),\s+Y\+(?<by>\d+)\nPrize:\s+X=(?<px>\d+),\s+Y=(?<py>\d+)\n?"#).unwrap();
@@ -71,6 +193,7 @@ fn parse_input(input: &str) -> Vec<Configuration> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rstest::rstest;
 
     #[test]
     fn test_part_one() {
@@ -83,4 +206,66 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(875318608908));
     }
+
+    fn config(a: (i64, i64), b: (i64, i64), price: (i64, i64)) -> Configuration {
+        Configuration {
+            a: Pos::new(a.0, a.1),
+            b: Pos::new(b.0, b.1),
+            price: Pos::new(price.0, price.1),
+        }
+    }
+
+    #[rstest]
+    #[case(config((1, 1), (2, 2), (7, 7)), 3, 1, Some(6))]
+    #[case(config((1, 1), (2, 2), (7, 7)), 1, 3, Some(7))]
+    #[case(config((1, 1), (2, 2), (7, 8)), 3, 1, None)]
+    #[case(config((2, 2), (4, 4), (7, 7)), 3, 1, None)]
+    #[case(config((6, 3), (4, 2), (14, 7)), 3, 1, Some(5))]
+    #[case(config((6, 3), (4, 2), (14, 7)), 1, 3, Some(7))]
+    #[case(config((4, 2), (6, 3), (8, 4)), 1, 1, Some(2))]
+    #[case(config((0, 0), (3, 3), (9, 9)), 3, 1, Some(3))]
+    fn test_collinear(
+        #[case] config: Configuration,
+        #[case] cost_a: Cost,
+        #[case] cost_b: Cost,
+        #[case] expected: Option<Cost>,
+    ) {
+        assert_eq!(calculate_config(&config, cost_a, cost_b), expected);
+    }
+
+    #[test]
+    fn test_negative_presses() {
+        // the unique solution presses A -1 times.
+        let config = config((2, 1), (1, 1), (1, 2));
+        let Pricing { cost_a, cost_b, .. } = Pricing::DEFAULT;
+        assert_eq!(calculate_config(&config, cost_a, cost_b), None);
+    }
+
+    #[test]
+    fn test_pricing_from_args() {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        assert_eq!(Pricing::from_args(&args(&["13"])), Ok(Pricing::DEFAULT));
+        assert_eq!(
+            Pricing::from_args(&args(&["13", "--cost-b", "2", "--offset", "0"])),
+            Ok(Pricing {
+                cost_b: 2,
+                offset: 0,
+                ..Pricing::DEFAULT
+            })
+        );
+        assert!(Pricing::from_args(&args(&["13", "--cost-a"])).is_err());
+        assert!(Pricing::from_args(&args(&["13", "--cost-a", "-1"])).is_err());
+    }
+
+    #[test]
+    fn test_custom_pricing() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        // with part one's costs swapped, the two winnable machines cost 80 + 3 * 40 and 38 + 3 * 86.
+        let pricing = Pricing {
+            cost_a: 1,
+            cost_b: 3,
+            offset: 0,
+        };
+        assert_eq!(solve(&input, pricing, pricing.offset), Some(200 + 296));
+    }
 }
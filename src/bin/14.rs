@@ -1,7 +1,7 @@
 use glam::IVec2;
 use rustc_hash::FxHashSet;
 
-advent_of_code::solution!(14);
+advent_of_code::solution!(14, modes: { "--flood-fill" => verify_with_flood_fill });
 
 pub fn part_one(input: &str) -> Option<usize> {
     let robots = parse_input(input).unwrap();
@@ -11,14 +11,78 @@ pub fn part_one(input: &str) -> Option<usize> {
 
 pub fn part_two(input: &str) -> Option<usize> {
     let robots = parse_input(input).unwrap();
-    #[cfg(debug_assertions)]
-    let print = true;
-    #[cfg(not(debug_assertions))]
-    let print = false;
-    Some(seconds_until_xmas_tree(&robots, WIDTH, HEIGHT, 100, print).unwrap())
+    seconds_until_xmas_tree(&robots, WIDTH, HEIGHT, Strategy::Variance)
+}
+
+/// Runs both detectors and prints the tree found by flood filling, e.g. `cargo solve 14 -- --flood-fill`.
+fn verify_with_flood_fill(input: &str) {
+    let robots = parse_input(input).unwrap();
+    let variance = seconds_until_xmas_tree(&robots, WIDTH, HEIGHT, Strategy::Variance);
+    let flood_fill = Strategy::FloodFill {
+        neighbour_limit: 100,
+        print: true,
+    };
+    let flood_fill = seconds_until_xmas_tree(&robots, WIDTH, HEIGHT, flood_fill);
+    println!("variance: {variance:?}, flood fill: {flood_fill:?}");
+    if variance != flood_fill {
+        eprintln!("detectors disagree");
+        std::process::exit(advent_of_code::template::EXIT_FAILURE);
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+enum Strategy {
+    /// The robots are most clustered along x (y) when the tree is drawn. As x repeats every `width`
+    /// and y every `height` seconds, the times with the least variance along each axis are combined
+    /// with the chinese remainder theorem.
+    Variance,
+    /// Simulates every second until a connected group of at least `neighbour_limit` robots appears.
+    FloodFill { neighbour_limit: usize, print: bool },
 }
 
 fn seconds_until_xmas_tree(
+    robots: &[(Pos, Velocity)],
+    width: i32,
+    height: i32,
+    strategy: Strategy,
+) -> Option<usize> {
+    match strategy {
+        Strategy::Variance => find_tree_variance(robots, width, height),
+        Strategy::FloodFill {
+            neighbour_limit,
+            print,
+        } => find_tree_flood_fill(robots, width, height, neighbour_limit, print),
+    }
+}
+
+fn find_tree_variance(robots: &[(Pos, Velocity)], width: i32, height: i32) -> Option<usize> {
+    let least_variance = |period: i32, axis: fn(Pos) -> i32| {
+        (0..period).min_by_key(|&time| {
+            variance(
+                robots
+                    .iter()
+                    .map(|&(pos, vel)| (axis(pos) + axis(vel) * time).rem_euclid(period) as i64),
+            )
+        })
+    };
+    let time_x = least_variance(width, |pos| pos.x)?;
+    let time_y = least_variance(height, |pos| pos.y)?;
+    // time = time_x (mod width) and time = time_y (mod height)
+    (0..height)
+        .map(|k| time_x + k * width)
+        .find(|time| time % height == time_y)
+        .map(|time| time as usize)
+}
+
+/// Variance scaled by `n²`, which keeps it integral without changing the order.
+fn variance(values: impl Iterator<Item = i64>) -> i64 {
+    let (n, sum, sum_sq) = values.fold((0, 0, 0), |(n, sum, sum_sq), value| {
+        (n + 1, sum + value, sum_sq + value * value)
+    });
+    n * sum_sq - sum * sum
+}
+
+fn find_tree_flood_fill(
     robots: &[(Pos, Velocity)],
     width: i32,
     height: i32,
@@ -173,15 +237,53 @@ mod tests {
     fn test_part_two() {
         let input = advent_of_code::template::read_file("examples", DAY);
         let robots = parse_input(&input).unwrap();
-        let result = seconds_until_xmas_tree(&robots, WIDTH, HEIGHT, 6, true);
+        let strategy = Strategy::FloodFill {
+            neighbour_limit: 6,
+            print: true,
+        };
+        let result = seconds_until_xmas_tree(&robots, WIDTH, HEIGHT, strategy);
         assert_eq!(result, Some(6819));
     }
 
+    /// Robots that form a filled square at `time`, plus as many scattered ones.
+    fn robots_with_square_at(time: i32) -> Vec<(Pos, Velocity)> {
+        let mut seed = 42u32;
+        let mut random = |max: i32| {
+            seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+            ((seed >> 16) % max as u32) as i32
+        };
+        let mut robots = vec![];
+        for i in 0..200 {
+            let vel = Velocity::new(random(201) - 100, random(207) - 103);
+            let pos = if i % 2 == 0 {
+                let target = Pos::new(40 + i / 2 % 10, 50 + i / 20);
+                wrap(target - vel * time, WIDTH, HEIGHT)
+            } else {
+                Pos::new(random(WIDTH), random(HEIGHT))
+            };
+            robots.push((pos, vel));
+        }
+        robots
+    }
+
+    #[test]
+    fn test_part_two_strategies() {
+        let robots = robots_with_square_at(7037);
+        let result = seconds_until_xmas_tree(&robots, WIDTH, HEIGHT, Strategy::Variance);
+        assert_eq!(result, Some(7037));
+        let strategy = Strategy::FloodFill {
+            neighbour_limit: 100,
+            print: false,
+        };
+        let result = seconds_until_xmas_tree(&robots, WIDTH, HEIGHT, strategy);
+        assert_eq!(result, Some(7037));
+    }
+
     #[test]
     fn test_part_one_run_robot() {
         let width = 11;
         let height = 7;
-        let robots = vec![(Pos::new(2, 4), Velocity::new(2, -3))];
+        let robots = [(Pos::new(2, 4), Velocity::new(2, -3))];
         let result = walk_robot(robots[0], 1, width, height);
         assert_eq!(result, Pos::new(4, 1));
         let result = walk_robot(robots[0], 2, width, height);
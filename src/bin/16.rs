@@ -1,8 +1,8 @@
 use arrayvec::ArrayVec;
 use glam::{IVec2, UVec2};
-use pathfinding::prelude::{astar_bag, dijkstra};
 use roaring::RoaringBitmap;
-use rustc_hash::FxHashSet;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
 
 advent_of_code::solution!(16);
 
@@ -17,19 +17,16 @@ pub fn part_two(input: &str) -> Option<usize> {
 }
 
 fn find_target(map: Map2, start: Elk, end: End) -> Cost {
-    let succ = successor_fn(move |pos| map.is_walkable(pos));
-    if let Some((_, cost)) = dijkstra(&start, succ, |&(pos, _)| pos == end) {
-        cost
-    } else {
-        panic!("unable to find shortest path to target!");
-    }
+    let from_start = map.distances(&[start], successor_fn(|pos| map.is_walkable(pos)));
+    map.best_at(&from_start, end)
+        .expect("unable to find shortest path to target!")
 }
 
 fn successor_fn(is_valid: impl Fn(Pos) -> bool) -> impl Fn(&Elk) -> ArrayVec<(Elk, Cost), 3> {
     move |&(pos, dir): &(Pos, Direction)| -> ArrayVec<((Pos, Direction), Cost), 3> {
         let mut candidates = ArrayVec::new();
         let forward = pos + dir.to_dir();
-        if is_valid(pos) {
+        if is_valid(forward) {
             candidates.push(((forward, dir), 1));
         }
         candidates.push(((pos, dir.rotate_left()), 1000));
@@ -38,21 +35,41 @@ fn successor_fn(is_valid: impl Fn(Pos) -> bool) -> impl Fn(&Elk) -> ArrayVec<(El
     }
 }
 
+/// The reversed edges of [`successor_fn`]: the states that can move into `(pos, dir)`.
+fn predecessor_fn(is_valid: impl Fn(Pos) -> bool) -> impl Fn(&Elk) -> ArrayVec<(Elk, Cost), 3> {
+    move |&(pos, dir): &(Pos, Direction)| -> ArrayVec<((Pos, Direction), Cost), 3> {
+        let mut candidates = ArrayVec::new();
+        let backward = pos - dir.to_dir();
+        if is_valid(backward) {
+            candidates.push(((backward, dir), 1));
+        }
+        candidates.push(((pos, dir.rotate_left()), 1000));
+        candidates.push(((pos, dir.rotate_right()), 1000));
+        candidates
+    }
+}
+
+/// Counts the tiles on any best route: a state lies on one if its distance from the start plus its
+/// distance to the end (searched over reversed edges) equals the best score.
 fn find_target_route(map: Map2, start: Elk, end: End) -> usize {
-    let succ = successor_fn(move |pos| map.is_walkable(pos));
-    let mut visited = FxHashSet::default();
-    for path in astar_bag(
-        &start,
-        succ,
-        |(pos, _)| pos.dot(end) as _,
-        |&(pos, _)| pos == end,
-    )
-    .unwrap()
-    .0
-    {
-        visited.extend(path.iter().map(|&(pos, _)| pos));
-    }
-    visited.len()
+    let is_valid = |pos| map.is_walkable(pos);
+    let from_start = map.distances(&[start], successor_fn(is_valid));
+    let best = map
+        .best_at(&from_start, end)
+        .expect("unable to find shortest path to target!");
+    let ends = Direction::ALL.map(|dir| (end, dir));
+    let to_end = map.distances(&ends, predecessor_fn(is_valid));
+
+    map.map
+        .iter()
+        .filter(|&idx| {
+            let pos = map.to_pos(idx);
+            Direction::ALL.into_iter().any(|dir| {
+                let state = map.to_state((pos, dir));
+                from_start[state].saturating_add(to_end[state]) == best
+            })
+        })
+        .count()
 }
 
 type Cost = u64;
@@ -84,6 +101,47 @@ impl Map2 {
         )
     }
 
+    #[inline]
+    fn to_state(&self, (pos, dir): Elk) -> usize {
+        self.to_idx(pos) as usize * 4 + dir as usize
+    }
+
+    /// Dijkstra from all `starts` over the `(pos, direction)` states, indexed by [`Map2::to_state`].
+    fn distances(
+        &self,
+        starts: &[Elk],
+        succ: impl Fn(&Elk) -> ArrayVec<(Elk, Cost), 3>,
+    ) -> Vec<Cost> {
+        let mut distances = vec![Cost::MAX; (self.dimension.x * self.dimension.y) as usize * 4];
+        let mut queue = BinaryHeap::new();
+        for &start in starts {
+            distances[self.to_state(start)] = 0;
+            queue.push(Reverse((0, start.0.to_array(), start.1)));
+        }
+        while let Some(Reverse((cost, pos, dir))) = queue.pop() {
+            let elk = (Pos::from_array(pos), dir);
+            if cost > distances[self.to_state(elk)] {
+                continue;
+            }
+            for (next, step) in succ(&elk) {
+                let state = self.to_state(next);
+                if cost + step < distances[state] {
+                    distances[state] = cost + step;
+                    queue.push(Reverse((cost + step, next.0.to_array(), next.1)));
+                }
+            }
+        }
+        distances
+    }
+
+    fn best_at(&self, distances: &[Cost], pos: Pos) -> Option<Cost> {
+        Direction::ALL
+            .into_iter()
+            .map(|dir| distances[self.to_state((pos, dir))])
+            .min()
+            .filter(|&cost| cost != Cost::MAX)
+    }
+
     #[inline]
     fn set_walkable(&mut self, pos: Pos) {
         self.map.insert(self.to_idx(pos));
@@ -98,11 +156,11 @@ impl Map2 {
 fn parse_input(input: &str) -> (Map2, Elk, End) {
     let mut start = None;
     let mut end = None;
-    let width = input.find('\n').unwrap();
-    let height = input.len() / width;
+    let width = input.find('\n').unwrap_or(input.len());
+    let height = input.lines().count();
     let mut map2 = Map2 {
         map: RoaringBitmap::default(),
-        dimension: UVec2::new(height as u32, width as u32),
+        dimension: UVec2::new(width as u32, height as u32),
     };
     for (y, line) in input.lines().enumerate() {
         for (x, c) in line.chars().enumerate() {
@@ -124,7 +182,7 @@ fn parse_input(input: &str) -> (Map2, Elk, End) {
     (map2, (start.unwrap(), Direction::Right), end.unwrap())
 }
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
 enum Direction {
     Up,
     Right,
@@ -133,6 +191,13 @@ enum Direction {
 }
 
 impl Direction {
+    const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
     fn to_dir(self) -> Pos {
         match self {
             Direction::Up => Pos::new(0, -1),
@@ -178,6 +243,13 @@ mod tests {
         assert_eq!(result, Some(45));
     }
 
+    #[test]
+    fn test_second_example() {
+        let input = advent_of_code::template::read_file_part("examples", DAY, 2);
+        assert_eq!(part_one(&input), Some(11048));
+        assert_eq!(part_two(&input), Some(64));
+    }
+
     #[test]
     fn test_map() {
         let mut map = Map2 {
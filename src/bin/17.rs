@@ -2,9 +2,17 @@
 
 use crate::SingleInstructionResult::{Continue, Output};
 use itertools::Itertools;
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+use std::io::Write;
+use std::str::FromStr;
+use tinyjson::JsonValue;
 use SingleInstructionResult::IncreaseInstructionPtr;
 
-advent_of_code::solution!(17);
+advent_of_code::solution!(17, modes: {
+    "--disasm" => print_disassembly,
+    "--trace" => print_trace,
+});
 
 pub fn part_one(input: &str) -> Option<String> {
     let (mut computer, program) = parse_input(input);
@@ -15,8 +23,9 @@ pub fn part_one(input: &str) -> Option<String> {
     )
 }
 
-pub fn part_two(_: &str) -> Option<u32> {
-    None
+pub fn part_two(input: &str) -> Option<Register> {
+    let (computer, program) = parse_input(input);
+    find_quine(computer, &program)
 }
 
 fn execute_instructions(computer: &mut Computer, program: &[Byte]) -> Vec<Byte> {
@@ -27,6 +36,36 @@ fn execute_instructions(computer: &mut Computer, program: &[Byte]) -> Vec<Byte>
     output
 }
 
+/// Finds the lowest initial register A for which the program outputs itself.
+///
+/// Every loop of the program shifts A by three bits, so the last output only depends on the
+/// highest three bits of A. Building A from the end of the program, one octal digit at a time,
+/// only keeps the candidates whose output already matches the tail of the program.
+fn find_quine(computer: Computer, program: &[Byte]) -> Option<Register> {
+    let mut candidates = vec![0];
+    for start in (0..program.len()).rev() {
+        candidates = candidates
+            .iter()
+            .flat_map(|&a| (0..8).map(move |digit| a << 3 | digit))
+            .filter(|&a| outputs(Computer { a, ..computer }, program, &program[start..]))
+            .collect();
+    }
+    candidates.into_iter().min()
+}
+
+/// Checks whether running the program yields exactly `expected`, stopping at the first mismatch.
+fn outputs(computer: Computer, program: &[Byte], expected: &[Byte]) -> bool {
+    let mut computer = computer;
+    let mut expected = expected.iter();
+    while let Ok((next, Some(byte))) = computer.ro_interpret_program(program) {
+        if expected.next() != Some(&byte) {
+            return false;
+        }
+        computer = next;
+    }
+    expected.next().is_none()
+}
+
 #[allow(dead_code)]
 fn ro_execute_instructions(computer: Computer, program: &[Byte]) -> (Computer, Vec<Byte>) {
     let mut output = vec![];
@@ -215,6 +254,560 @@ enum SingleInstructionResult {
     IncreaseInstructionPtr,
 }
 
+/// Prints the disassembled program and the symbolic expression of every output.
+fn print_disassembly(input: &str) {
+    let (_, program) = parse_input(input);
+    let (Ok(disassembly), Ok(outputs)) =
+        (disassemble(&program), decompile(&program, program.len()))
+    else {
+        eprintln!("Program contains invalid instructions.");
+        return;
+    };
+    println!("Disassembly:");
+    for line in disassembly {
+        println!("  {line}");
+    }
+    println!();
+    println!("Outputs:");
+    for (i, expr) in outputs.iter().enumerate() {
+        println!("  out[{i}] = {expr}");
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Combo {
+    Value(Byte),
+    A,
+    B,
+    C,
+    Reserved,
+}
+
+impl From<Byte> for Combo {
+    fn from(value: Byte) -> Self {
+        match value {
+            0..=3 => Combo::Value(value),
+            4 => Combo::A,
+            5 => Combo::B,
+            6 => Combo::C,
+            _ => Combo::Reserved,
+        }
+    }
+}
+
+impl Display for Combo {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Combo::Value(value) => write!(f, "{value}"),
+            Combo::A => f.write_str("A"),
+            Combo::B => f.write_str("B"),
+            Combo::C => f.write_str("C"),
+            Combo::Reserved => f.write_str("?"),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Operand {
+    Literal(Byte),
+    Combo(Combo),
+    Ignored,
+}
+
+impl Instr {
+    fn operand(self, operand: Byte) -> Operand {
+        match self {
+            Instr::Bxl | Instr::Jnz => Operand::Literal(operand),
+            Instr::Bxc => Operand::Ignored,
+            _ => Operand::Combo(operand.into()),
+        }
+    }
+}
+
+impl Display for Operand {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Operand::Literal(value) => write!(f, "{value}"),
+            Operand::Combo(combo) => write!(f, "{combo}"),
+            Operand::Ignored => Ok(()),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+struct Disassembled {
+    pointer: Pointer,
+    instr: Instr,
+    operand: Operand,
+}
+
+impl Display for Disassembled {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let op = self.operand;
+        let effect = match self.instr {
+            Instr::Adv => format!("A = A >> {op}"),
+            Instr::Bxl => format!("B = B ^ {op}"),
+            Instr::Bst => format!("B = {op} % 8"),
+            Instr::Jnz => format!("if A != 0 jump {op}"),
+            Instr::Bxc => "B = B ^ C".to_string(),
+            Instr::Out => format!("out {op} % 8"),
+            Instr::Bdv => format!("B = A >> {op}"),
+            Instr::Cdv => format!("C = A >> {op}"),
+        };
+        let mnemonic = format!("{:?} {op}", self.instr);
+        write!(f, "{:02}: {mnemonic:<6} ; {effect}", self.pointer)
+    }
+}
+
+fn disassemble(program: &[Byte]) -> Result<Vec<Disassembled>, Trap> {
+    (0..program.len())
+        .step_by(2)
+        .map(|pointer| {
+            let instr = Instr::try_from(program[pointer])?;
+            let operand = *program.get(pointer + 1).ok_or(Trap)?;
+            Ok(Disassembled {
+                pointer,
+                instr,
+                operand: instr.operand(operand),
+            })
+        })
+        .collect()
+}
+
+/// Expression over the initial registers, as produced by [`decompile`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Expr {
+    A,
+    B,
+    C,
+    Value(Register),
+    Shr(Box<Expr>, Box<Expr>),
+    Xor(Box<Expr>, Box<Expr>),
+    Mod8(Box<Expr>),
+}
+
+impl Expr {
+    fn shr(self, rhs: Expr) -> Expr {
+        match (self, rhs) {
+            (Expr::Value(a), Expr::Value(b)) => Expr::Value(a.checked_shr(b as u32).unwrap_or(0)),
+            (expr, Expr::Value(0)) => expr,
+            (Expr::Shr(expr, by), Expr::Value(b)) => match *by {
+                Expr::Value(a) => expr.shr(Expr::Value(a + b)),
+                by => Expr::Shr(
+                    Box::new(Expr::Shr(expr, Box::new(by))),
+                    Box::new(Expr::Value(b)),
+                ),
+            },
+            (lhs, rhs) => Expr::Shr(Box::new(lhs), Box::new(rhs)),
+        }
+    }
+
+    fn xor(self, rhs: Expr) -> Expr {
+        match (self, rhs) {
+            (Expr::Value(a), Expr::Value(b)) => Expr::Value(a ^ b),
+            (expr, Expr::Value(0)) | (Expr::Value(0), expr) => expr,
+            (Expr::Xor(expr, with), Expr::Value(b)) => match *with {
+                Expr::Value(a) => expr.xor(Expr::Value(a ^ b)),
+                with => Expr::Xor(
+                    Box::new(Expr::Xor(expr, Box::new(with))),
+                    Box::new(Expr::Value(b)),
+                ),
+            },
+            (lhs, rhs) => Expr::Xor(Box::new(lhs), Box::new(rhs)),
+        }
+    }
+
+    fn mod8(self) -> Expr {
+        match self {
+            Expr::Value(value) => Expr::Value(value % 8),
+            expr if expr.is_octal() => expr,
+            expr => Expr::Mod8(Box::new(expr)),
+        }
+    }
+
+    /// Whether the expression is known to be below 8.
+    fn is_octal(&self) -> bool {
+        match self {
+            Expr::Value(value) => *value < 8,
+            Expr::Mod8(_) => true,
+            Expr::Xor(lhs, rhs) => lhs.is_octal() && rhs.is_octal(),
+            _ => false,
+        }
+    }
+
+    fn is_compound(&self) -> bool {
+        matches!(self, Expr::Shr(..) | Expr::Xor(..) | Expr::Mod8(_))
+    }
+}
+
+impl Display for Expr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let operand = |expr: &Expr| {
+            if expr.is_compound() {
+                format!("({expr})")
+            } else {
+                expr.to_string()
+            }
+        };
+        match self {
+            Expr::A => f.write_str("A"),
+            Expr::B => f.write_str("B"),
+            Expr::C => f.write_str("C"),
+            Expr::Value(value) => write!(f, "{value}"),
+            Expr::Shr(lhs, rhs) => write!(f, "{} >> {}", operand(lhs), operand(rhs)),
+            Expr::Xor(lhs, rhs) => write!(f, "{} ^ {}", operand(lhs), operand(rhs)),
+            Expr::Mod8(expr) => write!(f, "{} % 8", operand(expr)),
+        }
+    }
+}
+
+/// Executes the program symbolically and returns the first `outputs` outputs as expressions of the
+/// initial registers. As the value of A is unknown, `Jnz` is assumed to jump until enough values
+/// were output (unless A is known to be zero).
+fn decompile(program: &[Byte], outputs: usize) -> Result<Vec<Expr>, Trap> {
+    const MAX_STEPS: usize = 10_000;
+    let (mut a, mut b, mut c) = (Expr::A, Expr::B, Expr::C);
+    let mut pointer = 0;
+    let mut output = vec![];
+
+    for _ in 0..MAX_STEPS {
+        if pointer >= program.len() || output.len() >= outputs {
+            break;
+        }
+        let instr = Instr::try_from(program[pointer])?;
+        let operand = *program.get(pointer + 1).ok_or(Trap)?;
+        let combo = match Combo::from(operand) {
+            Combo::Value(value) => Ok(Expr::Value(value)),
+            Combo::A => Ok(a.clone()),
+            Combo::B => Ok(b.clone()),
+            Combo::C => Ok(c.clone()),
+            Combo::Reserved => Err(Trap),
+        };
+        match instr {
+            Instr::Adv => a = a.clone().shr(combo?),
+            Instr::Bxl => b = b.xor(Expr::Value(operand)),
+            Instr::Bst => b = combo?.mod8(),
+            Instr::Jnz if a != Expr::Value(0) => {
+                pointer = operand as Pointer;
+                continue;
+            }
+            Instr::Jnz => {}
+            Instr::Bxc => b = b.xor(c.clone()),
+            Instr::Out => output.push(combo?.mod8()),
+            Instr::Bdv => b = a.clone().shr(combo?),
+            Instr::Cdv => c = a.clone().shr(combo?),
+        }
+        pointer += 2;
+    }
+
+    Ok(output)
+}
+
+/// Runs the program step by step and prints a table of every executed instruction.
+///
+/// `--json` dumps the trace as JSON instead, with registers as strings as they exceed the
+/// integers a JSON number can hold exactly. `--break <condition>` (repeatable) pauses at a
+/// pointer value (`--break 4`) or register condition (`--break "a<8"`) and prompts to step,
+/// continue or quit.
+fn print_trace(input: &str) {
+    let args: Vec<String> = std::env::args().collect();
+    let json = args.iter().any(|arg| arg == "--json");
+    let breakpoints = match advent_of_code::template::flag_values(&args, "--break") {
+        Ok(breakpoints) => breakpoints,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(advent_of_code::template::EXIT_FAILURE);
+        }
+    };
+
+    let (computer, program) = parse_input(input);
+    let mut debugger = Debugger::new(computer, &program, breakpoints);
+    let mut printed = 0;
+    let mut stop = match debugger.hit() {
+        Some(breakpoint) => Ok(Stop::Breakpoint(breakpoint)),
+        None => debugger.resume(),
+    };
+
+    if !json {
+        println!("{}", Step::TABLE_HEADER);
+    }
+    loop {
+        if !json {
+            for step in &debugger.trace()[printed..] {
+                println!("{step}");
+            }
+            printed = debugger.trace().len();
+        }
+        match stop {
+            Ok(Stop::Paused | Stop::Breakpoint(_)) if json => break,
+            Ok(Stop::Paused) => {}
+            Ok(Stop::Breakpoint(breakpoint)) => println!("-- breakpoint `{breakpoint}` hit"),
+            Ok(Stop::Halted) => break,
+            Ok(Stop::StepLimit) => {
+                eprintln!("Stopped after {} steps.", debugger.trace().len());
+                break;
+            }
+            Err(Trap) => {
+                eprintln!("Trap at pointer {}.", debugger.computer.pointer);
+                break;
+            }
+        }
+        print!("-- [s]tep, [c]ontinue or [q]uit? ");
+        let _ = std::io::stdout().flush();
+        let mut command = String::new();
+        if std::io::stdin().read_line(&mut command).unwrap_or(0) == 0 {
+            break;
+        }
+        stop = match command.trim() {
+            "" | "s" => debugger.step(),
+            "c" => debugger.resume(),
+            _ => break,
+        };
+    }
+
+    if json {
+        let steps = debugger.trace().iter().map(JsonValue::from).collect();
+        println!("{}", JsonValue::Array(steps).stringify().unwrap());
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Reg {
+    A,
+    B,
+    C,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Comparison {
+    const ALL: [(&'static str, Comparison); 6] = [
+        ("==", Comparison::Eq),
+        ("!=", Comparison::Ne),
+        ("<=", Comparison::Le),
+        (">=", Comparison::Ge),
+        ("<", Comparison::Lt),
+        (">", Comparison::Gt),
+    ];
+
+    fn symbol(self) -> &'static str {
+        Self::ALL.iter().find(|(_, cmp)| *cmp == self).unwrap().0
+    }
+}
+
+/// Condition on which the [`Debugger`] pauses, checked before every instruction.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Breakpoint {
+    Pointer(Pointer),
+    Register(Reg, Comparison, Register),
+}
+
+impl Breakpoint {
+    fn matches(&self, computer: &Computer) -> bool {
+        match *self {
+            Breakpoint::Pointer(pointer) => computer.pointer == pointer,
+            Breakpoint::Register(reg, cmp, value) => {
+                let register = match reg {
+                    Reg::A => computer.a,
+                    Reg::B => computer.b,
+                    Reg::C => computer.c,
+                };
+                match cmp {
+                    Comparison::Eq => register == value,
+                    Comparison::Ne => register != value,
+                    Comparison::Lt => register < value,
+                    Comparison::Le => register <= value,
+                    Comparison::Gt => register > value,
+                    Comparison::Ge => register >= value,
+                }
+            }
+        }
+    }
+}
+
+impl FromStr for Breakpoint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || format!("invalid breakpoint `{s}`, expecting e.g. `4`, `a==0` or `b<8`");
+        let s = s.replace(' ', "");
+        if let Ok(pointer) = s.parse() {
+            return Ok(Breakpoint::Pointer(pointer));
+        }
+        let reg = match s.chars().next().map(|c| c.to_ascii_lowercase()) {
+            Some('a') => Reg::A,
+            Some('b') => Reg::B,
+            Some('c') => Reg::C,
+            _ => return Err(error()),
+        };
+        let (cmp, value) = Comparison::ALL
+            .iter()
+            .find_map(|&(symbol, cmp)| Some((cmp, s[1..].strip_prefix(symbol)?)))
+            .ok_or_else(error)?;
+        let value = value.parse().map_err(|_| error())?;
+        Ok(Breakpoint::Register(reg, cmp, value))
+    }
+}
+
+impl Display for Breakpoint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Breakpoint::Pointer(pointer) => write!(f, "pointer == {pointer}"),
+            Breakpoint::Register(reg, cmp, value) => {
+                write!(f, "{reg:?} {} {value}", cmp.symbol())
+            }
+        }
+    }
+}
+
+/// A single executed instruction together with the state before and after it.
+#[derive(Copy, Clone, Debug)]
+struct Step {
+    instr: Instr,
+    operand: Operand,
+    before: Computer,
+    after: Computer,
+    output: Option<Byte>,
+}
+
+impl Step {
+    const TABLE_HEADER: &'static str = "ptr  instr               A before        B before        C before         A after         B after         C after  out";
+}
+
+impl Display for Step {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mnemonic = format!("{:?} {}", self.instr, self.operand);
+        let output = self.output.map(|out| out.to_string()).unwrap_or_default();
+        let (before, after) = (self.before, self.after);
+        write!(
+            f,
+            "{:>3}  {mnemonic:<6} {:>16}{:>16}{:>16}{:>16}{:>16}{:>16}  {output}",
+            before.pointer, before.a, before.b, before.c, after.a, after.b, after.c,
+        )
+    }
+}
+
+impl From<&Step> for JsonValue {
+    fn from(step: &Step) -> Self {
+        let registers = |computer: Computer| {
+            JsonValue::Object(HashMap::from([
+                ("a".to_string(), JsonValue::String(computer.a.to_string())),
+                ("b".to_string(), JsonValue::String(computer.b.to_string())),
+                ("c".to_string(), JsonValue::String(computer.c.to_string())),
+            ]))
+        };
+        JsonValue::Object(HashMap::from([
+            (
+                "pointer".to_string(),
+                JsonValue::Number(step.before.pointer as f64),
+            ),
+            (
+                "instruction".to_string(),
+                JsonValue::String(format!("{:?}", step.instr)),
+            ),
+            (
+                "operand".to_string(),
+                JsonValue::String(step.operand.to_string()),
+            ),
+            ("before".to_string(), registers(step.before)),
+            ("after".to_string(), registers(step.after)),
+            (
+                "output".to_string(),
+                step.output
+                    .map_or(JsonValue::Null, |out| JsonValue::Number(out as f64)),
+            ),
+        ]))
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Stop {
+    Paused,
+    Halted,
+    Breakpoint(Breakpoint),
+    StepLimit,
+}
+
+/// Executes a program instruction by instruction while recording every [`Step`].
+struct Debugger<'a> {
+    computer: Computer,
+    program: &'a [Byte],
+    breakpoints: Vec<Breakpoint>,
+    trace: Vec<Step>,
+}
+
+impl<'a> Debugger<'a> {
+    const MAX_STEPS: usize = 100_000;
+
+    fn new(computer: Computer, program: &'a [Byte], breakpoints: Vec<Breakpoint>) -> Self {
+        Debugger {
+            computer,
+            program,
+            breakpoints,
+            trace: vec![],
+        }
+    }
+
+    fn trace(&self) -> &[Step] {
+        &self.trace
+    }
+
+    fn hit(&self) -> Option<Breakpoint> {
+        self.breakpoints
+            .iter()
+            .find(|breakpoint| breakpoint.matches(&self.computer))
+            .copied()
+    }
+
+    /// Executes a single instruction.
+    fn step(&mut self) -> Result<Stop, Trap> {
+        if self.computer.pointer >= self.program.len() {
+            return Ok(Stop::Halted);
+        }
+        if self.trace.len() >= Self::MAX_STEPS {
+            return Ok(Stop::StepLimit);
+        }
+        let before = self.computer;
+        let instr = before.current(self.program)?;
+        let operand = instr.operand(before.literal(self.program)?);
+        let (after, output) = before.ro_single_instruction(self.program)?;
+        self.computer = after;
+        self.trace.push(Step {
+            instr,
+            operand,
+            before,
+            after,
+            output,
+        });
+        if after.pointer >= self.program.len() {
+            Ok(Stop::Halted)
+        } else {
+            Ok(Stop::Paused)
+        }
+    }
+
+    /// Executes instructions until the program halts or a breakpoint is hit.
+    fn resume(&mut self) -> Result<Stop, Trap> {
+        loop {
+            match self.step()? {
+                Stop::Paused => {}
+                stop => return Ok(stop),
+            }
+            if let Some(breakpoint) = self.hit() {
+                return Ok(Stop::Breakpoint(breakpoint));
+            }
+        }
+    }
+}
+
 fn parse_input(input: &str) -> (Computer, Program) {
     let mut iter = input.lines();
     let a = iter
@@ -269,8 +862,20 @@ mod tests {
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        let result = part_two(&advent_of_code::template::read_file_part(
+            "examples", DAY, 2,
+        ));
+        assert_eq!(result, Some(117440));
+    }
+
+    #[test]
+    fn test_quine_outputs_program() {
+        let (computer, program) = parse_input(&advent_of_code::template::read_file_part(
+            "examples", DAY, 2,
+        ));
+        let a = find_quine(computer, &program).unwrap();
+        let mut computer = Computer { a, ..computer };
+        assert_eq!(execute_instructions(&mut computer, &program), program);
     }
 
     #[rstest]
@@ -291,6 +896,106 @@ mod tests {
         assert_eq!(expected_output, output);
     }
 
+    #[test]
+    fn test_disassemble() {
+        let lines = disassemble(&[2, 4, 1, 3, 7, 5, 4, 1, 5, 5, 3, 0])
+            .unwrap()
+            .iter()
+            .map(ToString::to_string)
+            .collect_vec();
+        assert_eq!(
+            lines,
+            vec![
+                "00: Bst A  ; B = A % 8",
+                "02: Bxl 3  ; B = B ^ 3",
+                "04: Cdv B  ; C = A >> B",
+                "06: Bxc    ; B = B ^ C",
+                "08: Out B  ; out B % 8",
+                "10: Jnz 0  ; if A != 0 jump 0",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_decompile_quine() {
+        let outputs = decompile(&[0, 3, 5, 4, 3, 0], 3).unwrap();
+        assert_eq!(
+            outputs.iter().map(ToString::to_string).collect_vec(),
+            vec!["(A >> 3) % 8", "(A >> 6) % 8", "(A >> 9) % 8"]
+        );
+    }
+
+    #[test]
+    fn test_trace_until_halt() {
+        let (computer, program) =
+            parse_input(&advent_of_code::template::read_file("examples", DAY));
+        let mut debugger = Debugger::new(computer, &program, vec![]);
+        assert_eq!(debugger.resume().unwrap(), Stop::Halted);
+        let output = debugger
+            .trace()
+            .iter()
+            .filter_map(|step| step.output)
+            .join(",");
+        assert_eq!(output, "4,6,3,5,6,3,5,2,1,0");
+    }
+
+    #[rstest]
+    #[case("4", 4)]
+    #[case("a<100", 2)]
+    #[case("A != 729", 2)]
+    fn test_trace_breakpoints(#[case] condition: &str, #[case] pointer: Pointer) {
+        let (computer, program) =
+            parse_input(&advent_of_code::template::read_file("examples", DAY));
+        let breakpoint: Breakpoint = condition.parse().unwrap();
+        let mut debugger = Debugger::new(computer, &program, vec![breakpoint]);
+        assert_eq!(debugger.resume().unwrap(), Stop::Breakpoint(breakpoint));
+        assert_eq!(debugger.computer.pointer, pointer);
+    }
+
+    #[test]
+    fn test_trace_json() {
+        let (computer, program) =
+            parse_input("Register A: 10\nRegister B: 0\nRegister C: 0\n\nProgram: 5,4");
+        let mut debugger = Debugger::new(computer, &program, vec![]);
+        debugger.resume().unwrap();
+        let json = JsonValue::from(&debugger.trace()[0]);
+        assert_eq!(json["pointer"], JsonValue::Number(0.0));
+        assert_eq!(json["instruction"], JsonValue::String("Out".to_string()));
+        assert_eq!(json["after"]["a"], JsonValue::String("10".to_string()));
+        assert_eq!(json["output"], JsonValue::Number(2.0));
+        assert!("d==1".parse::<Breakpoint>().is_err());
+
+        let big = Computer {
+            a: (1 << 53) + 1,
+            ..computer
+        };
+        let mut debugger = Debugger::new(big, &program, vec![]);
+        debugger.resume().unwrap();
+        let json = JsonValue::from(&debugger.trace()[0]);
+        assert_eq!(
+            json["before"]["a"],
+            JsonValue::String("9007199254740993".to_string())
+        );
+    }
+
+    #[test]
+    fn test_breakpoint_flags() {
+        let breakpoints = |args: &[&str]| {
+            let args = args.iter().map(|arg| arg.to_string()).collect_vec();
+            advent_of_code::template::flag_values::<Breakpoint>(&args, "--break")
+        };
+        assert_eq!(
+            breakpoints(&["--trace", "--break", "4", "--break", "a<8"]),
+            Ok(vec![
+                Breakpoint::Pointer(4),
+                Breakpoint::Register(Reg::A, Comparison::Lt, 8)
+            ])
+        );
+        assert!(breakpoints(&["--trace", "--break"]).is_err());
+        assert!(breakpoints(&["--break", "--json"]).is_err());
+        assert!(breakpoints(&["--break", "d==1"]).is_err());
+    }
+
     // #[rstest]
     // #[case((0, 0, 9), vec![2, 6], (0, 1, 9), vec![])]
     // #[case((10, 0, 0), vec![5,0,5,1,5,4], (10, 0, 0), vec![0,1,2])]
@@ -3,8 +3,12 @@ use glam::{IVec2, UVec2};
 use pathfinding::directed::bfs::bfs;
 use roaring::RoaringBitmap;
 use rustc_hash::FxHashMap;
+use std::str::FromStr;
 
-advent_of_code::solution!(18);
+advent_of_code::solution!(18, modes: {
+    "--strategy" => solve_with_strategy,
+    "--compare" => compare_strategies,
+});
 
 pub fn part_one(input: &str) -> Option<usize> {
     let (map, end, _) = parse_input::<71, 71, 1024>(input);
@@ -13,7 +17,83 @@ pub fn part_one(input: &str) -> Option<usize> {
 
 pub fn part_two(input: &str) -> Option<String> {
     let (map, end, rest) = parse_input::<71, 71, 1024>(input);
-    Some(find_exit_try(map, end, rest).to_string())
+    Some(find_blocking_byte(map, end, rest, Strategy::Rerun))
+}
+
+/// Solves part two with the strategy given after the flag, e.g. `cargo solve 18 -- --strategy union-find`.
+///
+/// The part is run like any other, so `--time` benchmarks the strategy.
+fn solve_with_strategy(input: &str) {
+    let args: Vec<String> = std::env::args().collect();
+    let strategy = advent_of_code::template::flag_value::<Strategy>(&args, "--strategy")
+        .and_then(|strategy| strategy.ok_or_else(|| "`--strategy` expects a value".to_string()));
+    let strategy = match strategy {
+        Ok(strategy) => strategy,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(advent_of_code::template::EXIT_FAILURE);
+        }
+    };
+    let (map, end, rest) = parse_input::<71, 71, 1024>(input);
+    advent_of_code::template::runner::run_part(
+        |(map, rest)| Some(find_blocking_byte(map, end, rest, strategy)),
+        (map, rest),
+        DAY,
+        2,
+    );
+}
+
+/// Benches every part two strategy on the same input, e.g. `cargo solve 18 --release -- --compare`.
+fn compare_strategies(input: &str) {
+    let (map, end, rest) = parse_input::<71, 71, 1024>(input);
+    for (name, strategy) in Strategy::NAMES.into_iter().zip(Strategy::ALL) {
+        advent_of_code::template::runner::bench_labelled(
+            |(map, rest)| Some(find_blocking_byte(map, end, rest, strategy)),
+            (map.clone(), rest.clone()),
+            name,
+        );
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Strategy {
+    /// Re-runs a BFS whenever a byte lands on the current path.
+    Rerun,
+    /// Binary searches the number of fallen bytes that still leaves a path.
+    BinarySearch,
+    /// Lets all bytes fall, then removes them in reverse order, joining the freed cells with a
+    /// union–find until start and end are connected.
+    UnionFind,
+}
+
+impl Strategy {
+    const ALL: [Strategy; 3] = [Strategy::Rerun, Strategy::BinarySearch, Strategy::UnionFind];
+    const NAMES: [&'static str; 3] = ["rerun", "binary-search", "union-find"];
+}
+
+impl FromStr for Strategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::NAMES
+            .iter()
+            .position(|&name| name == s)
+            .map(|i| Self::ALL[i])
+            .ok_or_else(|| {
+                format!(
+                    "unknown strategy `{s}`, expecting one of: {}",
+                    Self::NAMES.join(", ")
+                )
+            })
+    }
+}
+
+fn find_blocking_byte(map: Map2, end: Pos, rest: Vec<Pos>, strategy: Strategy) -> String {
+    match strategy {
+        Strategy::Rerun => find_exit_try(map, end, rest),
+        Strategy::BinarySearch => find_exit_binary_search(map, end, &rest),
+        Strategy::UnionFind => find_exit_union_find(map, end, &rest),
+    }
 }
 
 fn find_exit(map: Map2, end: Pos) -> usize {
@@ -43,6 +123,94 @@ fn find_exit_try(mut map: Map2, end: Pos, rest: Vec<Pos>) -> String {
     panic!("No solution found")
 }
 
+fn find_exit_binary_search(map: Map2, end: Pos, rest: &[Pos]) -> String {
+    let has_exit = |fallen: usize| {
+        let mut map = map.clone();
+        for &pos in &rest[..fallen] {
+            map.set_unwalkable(pos);
+        }
+        let succ = successor_bfs(|pos| map.in_bounds(pos) && map.is_walkable(pos));
+        bfs(&Pos::new(0, 0), succ, |&pos| pos == end).is_some()
+    };
+    // the first number of fallen bytes without an exit, the last of them blocked it.
+    let (mut low, mut high) = (0, rest.len() + 1);
+    while low < high {
+        let mid = (low + high) / 2;
+        if has_exit(mid) {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    let r = rest.get(low.wrapping_sub(1)).expect("No solution found");
+    format!("{},{}", r.x, r.y)
+}
+
+fn find_exit_union_find(mut map: Map2, end: Pos, rest: &[Pos]) -> String {
+    for &pos in rest {
+        map.set_unwalkable(pos);
+    }
+    let mut sets = UnionFind::new((map.dimension.x * map.dimension.y) as usize);
+    let join = |sets: &mut UnionFind, map: &Map2, pos: Pos| {
+        let succ = successor_bfs(|pos| map.in_bounds(pos) && map.is_walkable(pos));
+        for to in succ(&pos) {
+            sets.union(map.to_idx(pos) as usize, map.to_idx(to) as usize);
+        }
+    };
+    for idx in map.map.iter() {
+        join(&mut sets, &map, map.to_pos(idx));
+    }
+    let (start, end) = (
+        map.to_idx(Pos::new(0, 0)) as usize,
+        map.to_idx(end) as usize,
+    );
+    if sets.find(start) == sets.find(end) {
+        panic!("No solution found")
+    }
+    for &r in rest.iter().rev() {
+        map.set_walkable(r);
+        join(&mut sets, &map, r);
+        if sets.find(start) == sets.find(end) {
+            return format!("{},{}", r.x, r.y);
+        }
+    }
+    panic!("No solution found")
+}
+
+struct UnionFind {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+}
+
+impl UnionFind {
+    fn new(len: usize) -> Self {
+        UnionFind {
+            parent: (0..len).collect(),
+            size: vec![1; len],
+        }
+    }
+
+    fn find(&mut self, mut node: usize) -> usize {
+        while self.parent[node] != node {
+            self.parent[node] = self.parent[self.parent[node]];
+            node = self.parent[node];
+        }
+        node
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return;
+        }
+        if self.size[a] < self.size[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+    }
+}
+
 fn successor_bfs(is_valid: impl Fn(Pos) -> bool) -> impl Fn(&Pos) -> ArrayVec<Pos, 4> {
     const DIRS: [Pos; 4] = [
         Pos::new(0, -1),
@@ -161,6 +329,8 @@ impl Map2 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use itertools::Itertools;
+    use rstest::rstest;
 
     #[test]
     fn test_part_one() {
@@ -178,6 +348,53 @@ mod tests {
         assert_eq!(result, "6,1".to_string());
     }
 
+    #[rstest]
+    #[case(Strategy::Rerun)]
+    #[case(Strategy::BinarySearch)]
+    #[case(Strategy::UnionFind)]
+    fn test_part_two_strategies(#[case] strategy: Strategy) {
+        let (map, end, stream) =
+            parse_input::<7, 7, 12>(&advent_of_code::template::read_file("examples", DAY));
+        let result = find_blocking_byte(map, end, stream, strategy);
+        assert_eq!(result, "6,1".to_string());
+    }
+
+    #[rstest]
+    #[case(Strategy::Rerun)]
+    #[case(Strategy::BinarySearch)]
+    #[case(Strategy::UnionFind)]
+    #[should_panic(expected = "No solution found")]
+    fn test_part_two_strategies_without_blocking_byte(#[case] strategy: Strategy) {
+        let (map, end, stream) = parse_input::<7, 7, 0>("1,1\n2,2\n");
+        find_blocking_byte(map, end, stream, strategy);
+    }
+
+    #[test]
+    fn test_strategies_agree() {
+        // every cell but start and end, falling in an order given by a multiplier coprime to 49.
+        for step in [2, 3, 5, 10, 19, 30, 48] {
+            let input = (1..48)
+                .map(|i| i * step % 49)
+                .filter(|&i| i != 0 && i != 48)
+                .map(|i| format!("{},{}", i % 7, i / 7))
+                .join("\n");
+            let (map, end, stream) = parse_input::<7, 7, 0>(&input);
+            let results = Strategy::ALL
+                .map(|strategy| find_blocking_byte(map.clone(), end, stream.clone(), strategy));
+            assert!(
+                results.iter().all_equal(),
+                "strategies disagree for step {step}: {results:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_strategy_names() {
+        assert_eq!("union-find".parse(), Ok(Strategy::UnionFind));
+        assert_eq!("binary-search".parse(), Ok(Strategy::BinarySearch));
+        assert!("dfs".parse::<Strategy>().is_err());
+    }
+
     #[test]
     fn test_cost() {
         let end = Pos::new(6, 6);
@@ -1,8 +1,5 @@
 advent_of_code::solution!(19);
 
-use itertools::Itertools;
-use rustc_hash::FxHashMap;
-
 pub fn part_one(input: &str) -> Option<usize> {
     let (towels, patterns) = parse_input(input);
     Some(find_matching_patterns(towels, patterns))
@@ -13,117 +10,106 @@ pub fn part_two(input: &str) -> Option<usize> {
     Some(find_matching_patterns_combinations(towels, patterns))
 }
 
-fn find_matching_patterns<'a>(towels: Towels<'a>, patterns: Patterns<'a>) -> usize {
+fn find_matching_patterns(towels: Towels, patterns: Patterns<'_>) -> usize {
     patterns
         .iter()
-        .filter(|pattern| can_match(pattern, &towels))
+        .filter(|pattern| towels.arrangements(pattern) > 0)
         .count()
 }
 
-fn find_matching_patterns_combinations<'a>(towels: Towels<'a>, patterns: Patterns<'a>) -> usize {
+fn find_matching_patterns_combinations(towels: Towels, patterns: Patterns<'_>) -> usize {
     patterns
         .iter()
-        .map(|pattern| count_match(pattern, &towels.without(pattern)))
+        .map(|pattern| towels.arrangements(pattern))
         .sum()
 }
 
-fn count_match<'a>(pattern: Pattern<'a>, towels: &Towels<'a>) -> usize {
-    fn count_match(
-        memoize: &mut FxHashMap<usize, usize>,
-        pattern: Pattern<'_>,
-        towels: &Towels<'_>,
-        current: usize,
-    ) -> usize {
-        if let Some(result) = memoize.get(&current) {
-            return *result;
-        }
-        let forward = |towel: Pattern<'_>| {
-            if current + towel.len() == pattern.len() {
-                1
-            } else {
-                count_match(memoize, pattern, towels, current + towel.len())
-            }
-        };
-        let count = (0..towels.max_len)
-            .filter(|pos| pos + current < pattern.len())
-            .map(|pos| &pattern[current..=current + pos])
-            .filter(|towel| towels.towels.contains(towel))
-            .map(forward)
-            .sum();
-        memoize.insert(current, count);
-        count
-    }
-    count_match(&mut FxHashMap::default(), pattern, towels, 0)
-}
-
-fn can_match<'a>(pattern: Pattern<'a>, towels: &Towels<'a>) -> bool {
-    let mut from = vec![0];
-    let mut covered = roaring::RoaringBitmap::new();
-
-    while let Some(position) = from.pop() {
-        if !covered.insert(position as u32) {
-            continue;
-        }
-        if (0..towels.max_len)
-            .filter(|pos| pos + position < pattern.len())
-            .map(|pos| &pattern[position..=position + pos])
-            .filter(|towel| towels.towels.contains(towel))
-            .inspect(|towel| from.push(position + towel.len()))
-            .any(|towel| position + towel.len() == pattern.len())
-        {
-            return true;
-        }
-    }
-    false
-}
-
 type Patterns<'a> = Vec<&'a str>;
 type Pattern<'a> = &'a str;
 
-fn parse_input(input: &str) -> (Towels<'_>, Patterns<'_>) {
+fn parse_input(input: &str) -> (Towels, Patterns<'_>) {
     let (towels, patterns) = input.split_once("\n\n").unwrap();
-    let towels = towels.split(',').map(|towel| towel.trim()).collect_vec();
-    let min_len = towels.iter().map(|towel| towel.len()).min().unwrap();
-    let max_len = towels.iter().map(|towel| towel.len()).max().unwrap();
+    let mut trie = Towels::default();
+    for towel in towels.split(',') {
+        trie.insert(towel.trim());
+    }
     let patterns = patterns.lines().map(|pattern| pattern.trim()).collect();
-    (
-        Towels {
-            towels,
-            min_len,
-            max_len,
-        },
-        patterns,
-    )
+    (trie, patterns)
 }
 
-struct Towels<'a> {
-    towels: Vec<Pattern<'a>>,
-    #[allow(dead_code)]
-    min_len: usize,
-    max_len: usize,
+/// Byte trie over all towels, the root is node `0`.
+struct Towels {
+    nodes: Vec<Node>,
 }
 
-impl<'a> Towels<'a> {
-    fn without(&self, pattern: Pattern<'a>) -> Self {
-        let towels = self
-            .towels
-            .iter()
-            .filter(|&&towel| towel != pattern)
-            .copied()
-            .collect_vec();
-        let min_len = towels.iter().map(|towel| towel.len()).min().unwrap();
-        let max_len = towels.iter().map(|towel| towel.len()).max().unwrap();
+#[derive(Clone, Default)]
+struct Node {
+    /// Children per lowercase letter, `0` if there is none (the root is never a child).
+    children: [u32; 26],
+    is_towel: bool,
+}
+
+impl Default for Towels {
+    fn default() -> Self {
         Towels {
-            towels,
-            min_len,
-            max_len,
+            nodes: vec![Node::default()],
         }
     }
 }
 
+impl Towels {
+    fn insert(&mut self, towel: Pattern<'_>) {
+        let mut node = 0;
+        for b in towel.bytes() {
+            let child = self.nodes[node].children[Self::letter(b)];
+            node = if child == 0 {
+                self.nodes.push(Node::default());
+                let child = self.nodes.len() - 1;
+                self.nodes[node].children[Self::letter(b)] = child as u32;
+                child
+            } else {
+                child as usize
+            };
+        }
+        self.nodes[node].is_towel = true;
+    }
+
+    /// Counts the ways `pattern` can be made from towels with a single forward pass: `ways[i]` is
+    /// the number of arrangements of `pattern[..i]`, and every towel found in the trie starting at
+    /// `i` extends them.
+    fn arrangements(&self, pattern: Pattern<'_>) -> usize {
+        let pattern = pattern.as_bytes();
+        let mut ways = vec![0; pattern.len() + 1];
+        ways[0] = 1;
+        for start in 0..pattern.len() {
+            if ways[start] == 0 {
+                continue;
+            }
+            let mut node = 0;
+            for (end, &b) in pattern.iter().enumerate().skip(start) {
+                node = self.nodes[node].children[Self::letter(b)] as usize;
+                if node == 0 {
+                    break;
+                }
+                if self.nodes[node].is_towel {
+                    ways[end + 1] += ways[start];
+                }
+            }
+        }
+        ways[pattern.len()]
+    }
+
+    #[inline]
+    fn letter(b: u8) -> usize {
+        debug_assert!(b.is_ascii_lowercase(), "unsupported stripe: {}", b as char);
+        (b - b'a') as usize
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rstest::rstest;
 
     #[test]
     fn test_part_one() {
@@ -136,4 +122,18 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(16));
     }
+
+    #[rstest]
+    #[case("brwrr", 2)]
+    #[case("bggr", 1)]
+    #[case("gbbr", 4)]
+    #[case("rrbgbr", 6)]
+    #[case("ubwu", 0)]
+    #[case("bwurrg", 1)]
+    #[case("brgr", 2)]
+    #[case("bbrwb", 0)]
+    fn test_arrangements(#[case] pattern: &str, #[case] expected: usize) {
+        let (towels, _) = parse_input(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(towels.arrangements(pattern), expected);
+    }
 }
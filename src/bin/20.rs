@@ -0,0 +1,207 @@
+use arrayvec::ArrayVec;
+use glam::{IVec2, UVec2};
+use rayon::prelude::*;
+use roaring::RoaringBitmap;
+use std::collections::VecDeque;
+
+advent_of_code::solution!(20);
+
+pub fn part_one(input: &str) -> Option<usize> {
+    let (map, start, end) = parse_input(input);
+    Some(count_cheats(&map, start, end, 2, 100))
+}
+
+pub fn part_two(input: &str) -> Option<usize> {
+    let (map, start, end) = parse_input(input);
+    Some(count_cheats(&map, start, end, 20, 100))
+}
+
+/// Counts the cheats of at most `max_cheat` picoseconds that save at least `threshold` picoseconds.
+///
+/// A cheat from `p` to `q` takes the distance from the start to `p`, the manhattan distance from `p`
+/// to `q` and the distance from `q` to the end.
+fn count_cheats(map: &Map2, start: Pos, end: Pos, max_cheat: i32, threshold: u32) -> usize {
+    let from_start = distance_field(map, start);
+    let to_end = distance_field(map, end);
+    let best = from_start[map.to_idx(end) as usize];
+    assert_ne!(best, UNREACHABLE, "no path from start to end");
+
+    map.map
+        .iter()
+        .collect::<Vec<_>>()
+        .par_iter()
+        .map(|&idx| {
+            let ds = from_start[idx as usize];
+            if ds == UNREACHABLE {
+                return 0;
+            }
+            let pos = map.to_pos(idx);
+            let mut count = 0;
+            for dy in -max_cheat..=max_cheat {
+                let reach = max_cheat - dy.abs();
+                for dx in -reach..=reach {
+                    let to = pos + Pos::new(dx, dy);
+                    if !map.in_bounds(to) || !map.is_walkable(to) {
+                        continue;
+                    }
+                    let de = to_end[map.to_idx(to) as usize];
+                    let cost = ds + (dx.abs() + dy.abs()) as u32 + de;
+                    if de != UNREACHABLE && cost + threshold <= best {
+                        count += 1;
+                    }
+                }
+            }
+            count
+        })
+        .sum()
+}
+
+const UNREACHABLE: u32 = u32::MAX / 2;
+
+/// Breadth-first search from `from`, returning the distance to every tile indexed by [`Map2::to_idx`].
+fn distance_field(map: &Map2, from: Pos) -> Vec<u32> {
+    let mut distances = vec![UNREACHABLE; (map.dimension.x * map.dimension.y) as usize];
+    let succ = successor_bfs(|pos| map.in_bounds(pos) && map.is_walkable(pos));
+    let mut queue = VecDeque::from([from]);
+    distances[map.to_idx(from) as usize] = 0;
+    while let Some(pos) = queue.pop_front() {
+        let distance = distances[map.to_idx(pos) as usize];
+        for to in succ(&pos) {
+            let idx = map.to_idx(to) as usize;
+            if distances[idx] == UNREACHABLE {
+                distances[idx] = distance + 1;
+                queue.push_back(to);
+            }
+        }
+    }
+    distances
+}
+
+fn successor_bfs(is_valid: impl Fn(Pos) -> bool) -> impl Fn(&Pos) -> ArrayVec<Pos, 4> {
+    const DIRS: [Pos; 4] = [
+        Pos::new(0, -1),
+        Pos::new(0, 1),
+        Pos::new(-1, 0),
+        Pos::new(1, 0),
+    ];
+    move |&pos: &Pos| -> ArrayVec<Pos, 4> {
+        let mut candidates = ArrayVec::new();
+        for dir in DIRS {
+            let to = pos + dir;
+            if is_valid(to) {
+                candidates.push(to);
+            }
+        }
+        candidates
+    }
+}
+
+fn parse_input(input: &str) -> (Map2, Pos, Pos) {
+    let mut start = None;
+    let mut end = None;
+    let width = input.find('\n').unwrap_or(input.len());
+    let height = input.lines().count();
+    let mut map = Map2 {
+        map: RoaringBitmap::new(),
+        dimension: UVec2::new(width as u32, height as u32),
+    };
+    for (y, line) in input.lines().enumerate() {
+        for (x, c) in line.chars().enumerate() {
+            let pos = Pos::new(x as i32, y as i32);
+            match c {
+                '#' => (),
+                '.' => map.set_walkable(pos),
+                'S' => {
+                    map.set_walkable(pos);
+                    start = Some(pos);
+                }
+                'E' => {
+                    map.set_walkable(pos);
+                    end = Some(pos);
+                }
+                other => panic!("unsupported character found: {other}"),
+            }
+        }
+    }
+    (map, start.unwrap(), end.unwrap())
+}
+
+type Pos = IVec2;
+
+#[derive(Clone, Debug)]
+struct Map2 {
+    map: RoaringBitmap,
+    dimension: UVec2,
+}
+
+impl Map2 {
+    #[inline]
+    fn to_idx(&self, pos: Pos) -> u32 {
+        debug_assert!(self.dimension.x > pos.x as u32);
+        debug_assert!(self.dimension.y > pos.y as u32);
+        pos.x as u32 + (pos.y as u32 * self.dimension.x)
+    }
+
+    #[inline]
+    fn to_pos(&self, idx: u32) -> Pos {
+        debug_assert!(self.dimension.x * self.dimension.y > idx);
+        Pos::new(
+            (idx % self.dimension.x) as i32,
+            (idx / self.dimension.x) as i32,
+        )
+    }
+
+    #[inline]
+    fn set_walkable(&mut self, pos: Pos) {
+        self.map.insert(self.to_idx(pos));
+    }
+
+    #[inline]
+    fn is_walkable(&self, pos: Pos) -> bool {
+        self.map.contains(self.to_idx(pos))
+    }
+
+    #[inline]
+    fn in_bounds(&self, pos: Pos) -> bool {
+        pos.x >= 0
+            && pos.y >= 0
+            && pos.x < self.dimension.x as i32
+            && pos.y < self.dimension.y as i32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(64, 1)]
+    #[case(40, 2)]
+    #[case(20, 5)]
+    #[case(12, 8)]
+    #[case(2, 44)]
+    fn test_part_one(#[case] threshold: u32, #[case] expected: usize) {
+        let (map, start, end) = parse_input(&advent_of_code::template::read_file("examples", DAY));
+        let result = count_cheats(&map, start, end, 2, threshold);
+        assert_eq!(result, expected);
+    }
+
+    #[rstest]
+    #[case(76, 3)]
+    #[case(74, 7)]
+    #[case(50, 285)]
+    fn test_part_two(#[case] threshold: u32, #[case] expected: usize) {
+        let (map, start, end) = parse_input(&advent_of_code::template::read_file("examples", DAY));
+        let result = count_cheats(&map, start, end, 20, threshold);
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_distance_field() {
+        let (map, start, end) = parse_input(&advent_of_code::template::read_file("examples", DAY));
+        let distances = distance_field(&map, start);
+        assert_eq!(distances[map.to_idx(end) as usize], 84);
+        assert_eq!(distances[map.to_idx(Pos::new(0, 0)) as usize], UNREACHABLE);
+    }
+}
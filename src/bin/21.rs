@@ -0,0 +1,178 @@
+use arrayvec::ArrayVec;
+use glam::IVec2;
+use rustc_hash::FxHashMap;
+
+advent_of_code::solution!(21);
+
+pub fn part_one(input: &str) -> Option<u64> {
+    Some(complexities(input, 2))
+}
+
+pub fn part_two(input: &str) -> Option<u64> {
+    Some(complexities(input, 25))
+}
+
+fn complexities(input: &str, robots: usize) -> u64 {
+    let mut memo = Memo::default();
+    input
+        .lines()
+        .map(|code| {
+            let numeric: u64 = code.trim_end_matches('A').parse().unwrap();
+            numeric * code_cost(code, robots, &mut memo)
+        })
+        .sum()
+}
+
+/// Minimal number of button presses on the outermost keypad to type `code` on the numeric keypad,
+/// operated through `robots` layers of directional keypads.
+fn code_cost(code: &str, robots: usize, memo: &mut Memo) -> u64 {
+    sequence_cost(&NUMERIC, code.as_bytes(), robots, memo)
+}
+
+/// Cost of pressing `sequence` on `keypad`, starting (as every robot does) on `A`.
+fn sequence_cost(keypad: &Keypad, sequence: &[u8], depth: usize, memo: &mut Memo) -> u64 {
+    let mut from = b'A';
+    let mut cost = 0;
+    for &to in sequence {
+        cost += move_cost(keypad, from, to, depth, memo);
+        from = to;
+    }
+    cost
+}
+
+/// Cost of moving from `from` to `to` on `keypad` and pressing it, with `depth` directional keypads
+/// between it and the human.
+fn move_cost(keypad: &Keypad, from: u8, to: u8, depth: usize, memo: &mut Memo) -> u64 {
+    if depth == 0 {
+        return keypad.paths(from, to)[0].len() as u64;
+    }
+    // the numeric keypad only appears at the top, so only the directional layers are shared.
+    let key = (from, to, depth);
+    if keypad.is_directional() {
+        if let Some(&cost) = memo.get(&key) {
+            return cost;
+        }
+    }
+    let cost = keypad
+        .paths(from, to)
+        .iter()
+        .map(|path| sequence_cost(&DIRECTIONAL, path, depth - 1, memo))
+        .min()
+        .unwrap();
+    if keypad.is_directional() {
+        memo.insert(key, cost);
+    }
+    cost
+}
+
+type Memo = FxHashMap<(u8, u8, usize), u64>;
+type Pos = IVec2;
+type Path = ArrayVec<u8, 6>;
+
+struct Keypad {
+    rows: &'static [&'static [u8]],
+}
+
+/// `.` marks the gap no robot arm may ever point at.
+const NUMERIC: Keypad = Keypad {
+    rows: &[b"789", b"456", b"123", b".0A"],
+};
+const DIRECTIONAL: Keypad = Keypad {
+    rows: &[b".^A", b"<v>"],
+};
+
+impl Keypad {
+    fn is_directional(&self) -> bool {
+        self.rows.len() == DIRECTIONAL.rows.len()
+    }
+
+    fn position(&self, key: u8) -> Pos {
+        self.rows
+            .iter()
+            .enumerate()
+            .find_map(|(y, row)| {
+                let x = row.iter().position(|&k| k == key)?;
+                Some(Pos::new(x as i32, y as i32))
+            })
+            .unwrap_or_else(|| panic!("unknown key: {}", key as char))
+    }
+
+    /// The (at most two) shortest paths worth considering: all horizontal moves first or all
+    /// vertical moves first, each followed by `A`. Zig-zagging is never cheaper, as every change of
+    /// direction costs extra presses on the keypads above.
+    fn paths(&self, from: u8, to: u8) -> ArrayVec<Path, 2> {
+        let (from, to) = (self.position(from), self.position(to));
+        let gap = self.position(b'.');
+        let delta = to - from;
+        let horizontal = if delta.x < 0 { b'<' } else { b'>' };
+        let vertical = if delta.y < 0 { b'^' } else { b'v' };
+        let steps = |key, n: i32| std::iter::repeat_n(key, n.unsigned_abs() as usize);
+
+        let mut paths = ArrayVec::new();
+        if Pos::new(to.x, from.y) != gap {
+            let mut path: Path = steps(horizontal, delta.x)
+                .chain(steps(vertical, delta.y))
+                .collect();
+            path.push(b'A');
+            paths.push(path);
+        }
+        if Pos::new(from.x, to.y) != gap && delta.x != 0 && delta.y != 0 {
+            let mut path: Path = steps(vertical, delta.y)
+                .chain(steps(horizontal, delta.x))
+                .collect();
+            path.push(b'A');
+            paths.push(path);
+        }
+        // both corners can't be the gap, so there always is a path.
+        debug_assert!(!paths.is_empty());
+        paths
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[test]
+    fn test_part_one() {
+        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Some(126384));
+    }
+
+    #[test]
+    fn test_part_two() {
+        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Some(154115708116294));
+    }
+
+    #[rstest]
+    #[case("029A", 68)]
+    #[case("980A", 60)]
+    #[case("179A", 68)]
+    #[case("456A", 64)]
+    #[case("379A", 64)]
+    fn test_code_cost(#[case] code: &str, #[case] expected: u64) {
+        assert_eq!(code_cost(code, 2, &mut Memo::default()), expected);
+    }
+
+    #[rstest]
+    #[case(&NUMERIC, b'A', b'1', &["^<<A"])]
+    #[case(&NUMERIC, b'1', b'0', &[">vA"])]
+    #[case(&NUMERIC, b'9', b'1', &["<<vvA", "vv<<A"])]
+    #[case(&DIRECTIONAL, b'<', b'^', &[">^A"])]
+    #[case(&DIRECTIONAL, b'A', b'A', &["A"])]
+    fn test_paths_avoid_gap(
+        #[case] keypad: &Keypad,
+        #[case] from: u8,
+        #[case] to: u8,
+        #[case] expected: &[&str],
+    ) {
+        let paths = keypad.paths(from, to);
+        let paths: Vec<_> = paths
+            .iter()
+            .map(|p| std::str::from_utf8(p).unwrap())
+            .collect();
+        assert_eq!(paths, expected);
+    }
+}
@@ -1,6 +1,4 @@
-use itertools::{iterate, Itertools};
-use rayon::iter::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterator};
-use rustc_hash::{FxHashMap, FxHashSet};
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
 use std::iter::successors;
 use std::num::Wrapping;
 
@@ -28,66 +26,75 @@ fn calculate(secret: &[Secret]) -> Secret {
 }
 
 fn calculate_with_diffs(secrets: &[Secret]) -> TotalCost {
-    let valid_sequences_with_costs: Vec<_> = secrets
+    price_table(secrets).into_iter().max().unwrap()
+}
+
+/// Sums, for every sequence of four price changes, the price each buyer sells at when first seeing it.
+///
+/// Every thread accumulates its buyers into its own dense table, the tables are added up at the end.
+fn price_table(secrets: &[Secret]) -> Vec<TotalCost> {
+    secrets
         .par_iter()
-        .map(|sec| find_sequences_with_costs(*sec))
-        .collect();
-    fn flatten_sequences(
-        mut acc: FxHashSet<Sequence>,
-        curr: &FxHashMap<Sequence, Cost>,
-    ) -> FxHashSet<Sequence> {
-        acc.extend(curr.keys());
-        acc
+        .enumerate()
+        .fold(Accumulator::new, |mut acc, (buyer, &secret)| {
+            acc.add_buyer(buyer as Stamp + 1, secret);
+            acc
+        })
+        .map(|acc| acc.totals)
+        .reduce(
+            || vec![0; SEQUENCES],
+            |mut totals, other| {
+                totals.iter_mut().zip(other).for_each(|(t, o)| *t += o);
+                totals
+            },
+        )
+}
+
+struct Accumulator {
+    totals: Vec<TotalCost>,
+    /// The last buyer (`+ 1`) that has seen each sequence, so it doesn't need to be reset per buyer.
+    seen: Vec<Stamp>,
+}
+
+impl Accumulator {
+    fn new() -> Self {
+        Accumulator {
+            totals: vec![0; SEQUENCES],
+            seen: vec![0; SEQUENCES],
+        }
     }
-    fn unify_sequences(
-        curr: FxHashSet<Sequence>,
-        next: FxHashSet<Sequence>,
-    ) -> FxHashSet<Sequence> {
-        curr.union(&next).copied().collect()
+
+    fn add_buyer(&mut self, stamp: Stamp, secret: Secret) {
+        let mut sequence = 0;
+        let mut state = (secret, 0, 0);
+        for i in 0..2000 {
+            state = sec_evo_price(state.0);
+            let (_, cost, delta) = state;
+            sequence = roll_sequence(sequence, delta);
+            // only the 4th change completes the first sequence
+            if i >= 3 && self.seen[sequence] != stamp {
+                self.seen[sequence] = stamp;
+                self.totals[sequence] += cost as TotalCost;
+            }
+        }
     }
-    let sequence_to_cost = |sequence: Sequence| -> TotalCost {
-        valid_sequences_with_costs
-            .par_iter()
-            .map(|curr| curr.get(&sequence))
-            .flatten()
-            .map(|&cost| cost as TotalCost)
-            .sum()
-    };
-    valid_sequences_with_costs
-        .par_iter()
-        .fold(FxHashSet::default, flatten_sequences)
-        .reduce(FxHashSet::default, unify_sequences)
-        .into_par_iter()
-        .map(sequence_to_cost)
-        .max()
-        .unwrap()
 }
 
-fn find_sequences_with_costs(secret: Secret) -> FxHashMap<Sequence, Cost> {
-    let mut sequences = FxHashMap::default();
-    for (s1, s2, s3, s4) in iterate((secret, 0, 0), |&sec| sec_evo_price(sec.0))
-        .take(2000)
-        .map(|(_, cost, delta)| (delta, cost))
-        .tuple_windows()
-    {
-        let mut sequence = (9i32 + s4.0) as Sequence;
-        sequence += ((9i32 + s3.0) as Sequence) << 5;
-        sequence += ((9i32 + s2.0) as Sequence) << 10;
-        sequence += ((9i32 + s1.0) as Sequence) << 15;
-        let cost = s4.1;
-        // inserting the first cost for the sequence
-        sequences.entry(sequence).or_insert(cost);
-    }
-    sequences
+/// Appends `delta` to the base 19 `sequence`, dropping its oldest change.
+#[inline]
+fn roll_sequence(sequence: Sequence, delta: DiffToPrev) -> Sequence {
+    sequence % (SEQUENCES / 19) * 19 + (delta + 9) as Sequence
 }
 
 type Secret = u64;
 type Cost = u8;
 type TotalCost = u32;
 type DiffToPrev = i32;
-// -9 to 9 = 19 possible values, which fit into 5 bits
-// diffs => 20 bits, so u32 will fit
-type Sequence = u32;
+// -9 to 9 = 19 possible values, four diffs are a base 19 number below 19^4
+type Sequence = usize;
+type Stamp = u32;
+
+const SEQUENCES: usize = 19 * 19 * 19 * 19;
 
 fn parse_input(input: &str) -> Vec<Secret> {
     input.lines().map(|line| line.parse().unwrap()).collect()
@@ -140,6 +147,21 @@ mod tests {
         assert_eq!(result, Some(23));
     }
 
+    #[test]
+    fn test_price_table() {
+        let table = price_table(&[1, 2, 3, 2024]);
+        let sequence = [-2, 1, -1, 3].into_iter().fold(0, roll_sequence);
+        assert_eq!(table[sequence], 23);
+        assert_eq!(table.iter().max(), Some(&23));
+    }
+
+    #[test]
+    fn test_roll_sequence() {
+        let sequence = [9, -9, 0, 5, 1].into_iter().fold(0, roll_sequence);
+        assert_eq!(sequence, [-9, 0, 5, 1].into_iter().fold(0, roll_sequence));
+        assert!(sequence < SEQUENCES);
+    }
+
     #[test]
     fn test_sec_evo() {
         let mut sec = 123;
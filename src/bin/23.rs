@@ -1,9 +1,12 @@
 #![feature(iter_next_chunk)]
 
 use itertools::Itertools;
+use pathfinding::undirected::connected_components::connected_components;
 use rustc_hash::{FxHashMap, FxHashSet};
+use std::collections::BTreeMap;
+use std::fmt::Write;
 
-advent_of_code::solution!(23);
+advent_of_code::solution!(23, modes: { "--dot" => print_dot });
 
 pub fn part_one(input: &str) -> Option<usize> {
     let networks = parse_input(input);
@@ -20,30 +23,259 @@ type Networks<'a> = FxHashMap<&'a str, Followers<'a>>;
 type LanNetworks<'a> = FxHashSet<[&'a str; 3]>;
 
 fn find_historian_largest(networks: Networks) -> String {
-    let lan_groups = build_lan_friends_map(&networks);
-    let max = lan_groups.iter().map(|x| x.len()).max().unwrap();
-    lan_groups
+    largest_clique(&networks).join(",")
+}
+
+fn largest_clique<'a>(networks: &Networks<'a>) -> Vec<&'a str> {
+    let graph = Graph::new(networks);
+    graph
+        .max_clique()
         .iter()
-        .filter(|x| x.len() == max)
-        .dedup()
-        .flatten()
+        .map(|node| graph.names[node])
         .sorted()
-        .join(",")
+        .collect()
+}
+
+/// Prints the network in graphviz DOT format with the triangles and the largest clique highlighted,
+/// and reports the degree distribution and connected components on stderr.
+///
+/// E.g. `cargo solve 23 -- --dot > lan.dot && sfdp -Tsvg lan.dot > lan.svg`.
+fn print_dot(input: &str) {
+    let networks = parse_input(input);
+    let triangles = build_network_map(&networks);
+    let clique = largest_clique(&networks);
+    print!("{}", to_dot(&networks, &triangles, &clique));
+
+    eprintln!(
+        "computers: {}, connections: {}, triangles: {}",
+        networks.len(),
+        networks.values().map(FxHashSet::len).sum::<usize>() / 2,
+        triangles.len()
+    );
+    eprintln!("degree distribution:");
+    for (degree, count) in degree_distribution(&networks) {
+        eprintln!("  {degree:>3}: {count}");
+    }
+    let components = components(&networks);
+    eprintln!(
+        "connected components: {} (sizes {})",
+        components.len(),
+        components.iter().map(Vec::len).join(", ")
+    );
+    eprintln!("largest clique ({}): {}", clique.len(), clique.join(","));
+}
+
+fn to_dot(networks: &Networks, triangles: &LanNetworks, clique: &[&str]) -> String {
+    let in_triangle: FxHashSet<_> = triangles.iter().flatten().copied().collect();
+    let triangle_edges: FxHashSet<_> = triangles
+        .iter()
+        .flat_map(|&[a, b, c]| [(a, b), (a, c), (b, c)])
+        .collect();
+
+    let mut dot = String::from("graph lan {\n");
+    for &computer in networks.keys().sorted() {
+        let style = if clique.contains(&computer) {
+            " [style=filled, fillcolor=gold]"
+        } else if in_triangle.contains(computer) {
+            " [style=filled, fillcolor=lightblue]"
+        } else {
+            ""
+        };
+        writeln!(dot, "    {computer}{style};").unwrap();
+    }
+    let edges = networks
+        .iter()
+        .flat_map(|(&one, followers)| followers.iter().map(move |&two| (one, two)))
+        .filter(|(one, two)| one < two)
+        .sorted();
+    for (one, two) in edges {
+        let style = if clique.contains(&one) && clique.contains(&two) {
+            " [color=gold, penwidth=3]"
+        } else if triangle_edges.contains(&(one, two)) {
+            " [color=steelblue]"
+        } else {
+            ""
+        };
+        writeln!(dot, "    {one} -- {two}{style};").unwrap();
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+/// Maps each degree to the number of computers with that many connections.
+fn degree_distribution(networks: &Networks) -> BTreeMap<usize, usize> {
+    networks
+        .values()
+        .map(FxHashSet::len)
+        .fold(BTreeMap::new(), |mut distribution, degree| {
+            *distribution.entry(degree).or_default() += 1;
+            distribution
+        })
+}
+
+/// The connected components as sorted computer names, largest first.
+fn components<'a>(networks: &Networks<'a>) -> Vec<Vec<&'a str>> {
+    let computers = networks.keys().copied().collect_vec();
+    connected_components(&computers, |computer| networks[computer].iter().copied())
+        .into_iter()
+        .map(|component| component.into_iter().sorted().collect_vec())
+        .sorted_by_key(|component| (usize::MAX - component.len(), component[0]))
+        .collect()
+}
+
+/// Enough bits for every two letter computer name.
+const WORDS: usize = (26 * 26usize).div_ceil(64);
+
+/// A set of interned nodes.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+struct BitSet([u64; WORDS]);
+
+impl BitSet {
+    fn full(len: usize) -> Self {
+        let mut set = BitSet::default();
+        for node in 0..len {
+            set.insert(node);
+        }
+        set
+    }
+
+    fn insert(&mut self, node: usize) {
+        self.0[node / 64] |= 1 << (node % 64);
+    }
+
+    fn remove(&mut self, node: usize) {
+        self.0[node / 64] &= !(1 << (node % 64));
+    }
+
+    fn len(&self) -> usize {
+        self.0.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.0.iter().all(|&word| word == 0)
+    }
+
+    fn and(&self, other: &Self) -> Self {
+        BitSet(std::array::from_fn(|i| self.0[i] & other.0[i]))
+    }
+
+    fn and_not(&self, other: &Self) -> Self {
+        BitSet(std::array::from_fn(|i| self.0[i] & !other.0[i]))
+    }
+
+    fn or(&self, other: &Self) -> Self {
+        BitSet(std::array::from_fn(|i| self.0[i] | other.0[i]))
+    }
+
+    fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.0.iter().enumerate().flat_map(|(i, &word)| {
+            let mut word = word;
+            std::iter::from_fn(move || {
+                (word != 0).then(|| {
+                    let bit = word.trailing_zeros() as usize;
+                    word &= word - 1;
+                    i * 64 + bit
+                })
+            })
+        })
+    }
+}
+
+/// `Networks` with the computer names interned into indices and adjacency stored as bitsets.
+struct Graph<'a> {
+    names: Vec<&'a str>,
+    adjacency: Vec<BitSet>,
 }
 
-fn build_lan_friends_map<'a>(networks: &Networks<'a>) -> Vec<Followers<'a>> {
-    let mut lan_groups = vec![];
-    for &candidate in networks.keys() {
-        lan_groups.push(FxHashSet::from_iter([candidate]));
+impl<'a> Graph<'a> {
+    fn new(networks: &Networks<'a>) -> Self {
+        let names = networks.keys().copied().sorted().collect_vec();
+        let index: FxHashMap<_, _> = names.iter().enumerate().map(|(i, &n)| (n, i)).collect();
+        let adjacency = names
+            .iter()
+            .map(|name| {
+                let mut neighbours = BitSet::default();
+                for friend in &networks[name] {
+                    neighbours.insert(index[friend]);
+                }
+                neighbours
+            })
+            .collect();
+        Graph { names, adjacency }
+    }
+
+    /// Orders the nodes by repeatedly removing the one with the fewest remaining neighbours, which
+    /// keeps the candidate sets of the outer Bron–Kerbosch loop small.
+    fn degeneracy_order(&self) -> Vec<usize> {
+        let mut remaining = BitSet::full(self.names.len());
+        let mut order = Vec::with_capacity(self.names.len());
+        while let Some(node) = remaining
+            .iter()
+            .min_by_key(|&node| self.adjacency[node].and(&remaining).len())
+        {
+            remaining.remove(node);
+            order.push(node);
+        }
+        order
+    }
+
+    /// Finds a maximum clique with the Bron–Kerbosch algorithm, using pivoting and a degeneracy
+    /// ordering of the outermost level.
+    fn max_clique(&self) -> BitSet {
+        let mut best = BitSet::default();
+        let mut candidates = BitSet::full(self.names.len());
+        let mut excluded = BitSet::default();
+        for node in self.degeneracy_order() {
+            let mut clique = BitSet::default();
+            clique.insert(node);
+            let neighbours = &self.adjacency[node];
+            self.bron_kerbosch(
+                clique,
+                candidates.and(neighbours),
+                excluded.and(neighbours),
+                &mut best,
+            );
+            candidates.remove(node);
+            excluded.insert(node);
+        }
+        best
     }
-    for lan_group in &mut lan_groups {
-        for (&friend, followers) in networks {
-            if lan_group.iter().all(|other| followers.contains(other)) {
-                lan_group.insert(friend);
+
+    fn bron_kerbosch(
+        &self,
+        clique: BitSet,
+        mut candidates: BitSet,
+        mut excluded: BitSet,
+        best: &mut BitSet,
+    ) {
+        if candidates.is_empty() {
+            if excluded.is_empty() && clique.len() > best.len() {
+                *best = clique;
             }
+            return;
+        }
+        if clique.len() + candidates.len() <= best.len() {
+            return;
+        }
+        let pivot = candidates
+            .or(&excluded)
+            .iter()
+            .max_by_key(|&node| candidates.and(&self.adjacency[node]).len())
+            .unwrap();
+        for node in candidates.and_not(&self.adjacency[pivot]).iter() {
+            let mut clique = clique;
+            clique.insert(node);
+            let neighbours = &self.adjacency[node];
+            self.bron_kerbosch(
+                clique,
+                candidates.and(neighbours),
+                excluded.and(neighbours),
+                best,
+            );
+            candidates.remove(node);
+            excluded.insert(node);
         }
     }
-    lan_groups
 }
 
 fn find_historian(networks: Networks, prefix: char) -> usize {
@@ -102,4 +334,52 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some("co,de,ka,ta".to_string()));
     }
+
+    #[test]
+    fn test_max_clique() {
+        // the triangle `aa,ab,ac` overlaps the larger clique in `ab`.
+        let input = "aa-ab\naa-ac\nab-ac\nab-ba\nab-bb\nab-bc\nba-bb\nba-bc\nbb-bc\naa-ba";
+        let result = part_two(input);
+        assert_eq!(result, Some("ab,ba,bb,bc".to_string()));
+    }
+
+    #[test]
+    fn test_dot() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let networks = parse_input(&input);
+        let triangles = build_network_map(&networks);
+        let dot = to_dot(&networks, &triangles, &largest_clique(&networks));
+        assert!(dot.starts_with("graph lan {\n"));
+        assert!(dot.contains("    co [style=filled, fillcolor=gold];"));
+        assert!(dot.contains("    aq [style=filled, fillcolor=lightblue];"));
+        assert!(dot.contains("    co -- de [color=gold, penwidth=3];"));
+        assert!(dot.contains("    aq -- cg [color=steelblue];"));
+    }
+
+    #[test]
+    fn test_graph_analysis() {
+        let input = "aa-ab\nab-ac\nba-bb";
+        let networks = parse_input(input);
+        assert_eq!(
+            degree_distribution(&networks),
+            BTreeMap::from([(1, 4), (2, 1)])
+        );
+        assert_eq!(
+            components(&networks),
+            [vec!["aa", "ab", "ac"], vec!["ba", "bb"]]
+        );
+    }
+
+    #[test]
+    fn test_bitset() {
+        let mut set = BitSet::default();
+        set.insert(3);
+        set.insert(64);
+        set.insert(600);
+        assert_eq!(set.iter().collect_vec(), [3, 64, 600]);
+        assert_eq!(set.len(), 3);
+        set.remove(64);
+        assert_eq!(set.iter().collect_vec(), [3, 600]);
+        assert_eq!(set.and_not(&BitSet::full(10)).iter().collect_vec(), [600]);
+    }
 }
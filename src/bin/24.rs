@@ -0,0 +1,292 @@
+use itertools::Itertools;
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::fmt::Write;
+
+advent_of_code::solution!(24, modes: { "--dot" => print_dot });
+
+pub fn part_one(input: &str) -> Option<u64> {
+    let circuit = parse_input(input);
+    Some(circuit.simulate())
+}
+
+pub fn part_two(input: &str) -> Option<String> {
+    let circuit = parse_input(input);
+    Some(circuit.miswired().into_iter().sorted().join(","))
+}
+
+/// Prints the circuit in graphviz DOT format, highlighting the wires flagged by the adder check.
+///
+/// E.g. `cargo solve 24 -- --dot | dot -Tsvg > circuit.svg`.
+fn print_dot(input: &str) {
+    let circuit = parse_input(input);
+    print!("{}", circuit.to_dot(&circuit.miswired()));
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Op {
+    And,
+    Or,
+    Xor,
+}
+
+impl Op {
+    fn apply(self, a: bool, b: bool) -> bool {
+        match self {
+            Op::And => a & b,
+            Op::Or => a | b,
+            Op::Xor => a ^ b,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Gate<'a> {
+    a: &'a str,
+    op: Op,
+    b: &'a str,
+    out: &'a str,
+}
+
+impl Gate<'_> {
+    fn has_input_wires(&self) -> bool {
+        is_input(self.a) && is_input(self.b)
+    }
+
+    fn is_first_bit(&self) -> bool {
+        self.a.ends_with("00") && self.b.ends_with("00")
+    }
+}
+
+fn is_input(wire: &str) -> bool {
+    wire.starts_with('x') || wire.starts_with('y')
+}
+
+struct Circuit<'a> {
+    inputs: FxHashMap<&'a str, bool>,
+    gates: Vec<Gate<'a>>,
+}
+
+impl<'a> Circuit<'a> {
+    /// Evaluates all gates and returns the number formed by the `z` wires.
+    fn simulate(&self) -> u64 {
+        let drivers: FxHashMap<_, _> = self.gates.iter().map(|gate| (gate.out, gate)).collect();
+        let mut values = self.inputs.clone();
+        self.outputs()
+            .rev()
+            .fold(0, |acc, z| acc << 1 | eval(z, &drivers, &mut values) as u64)
+    }
+
+    /// The `z` wires, least significant bit first.
+    fn outputs(&self) -> std::vec::IntoIter<&'a str> {
+        self.gates
+            .iter()
+            .map(|gate| gate.out)
+            .filter(|out| out.starts_with('z'))
+            .sorted()
+            .collect_vec()
+            .into_iter()
+    }
+
+    /// Finds the gate outputs that don't fit the structure of a ripple-carry adder, where bit `i`
+    /// is computed as
+    ///
+    /// ```text
+    /// x XOR y -> s      s XOR carry -> z
+    /// x AND y -> a      s AND carry -> b      a OR b -> carry'
+    /// ```
+    ///
+    /// and the last carry is the highest `z` wire.
+    fn miswired(&self) -> FxHashSet<&'a str> {
+        let last = self.outputs().last().unwrap_or_default();
+        let consumers = |wire: &str, op: Op| {
+            self.gates
+                .iter()
+                .any(|gate| gate.op == op && (gate.a == wire || gate.b == wire))
+        };
+
+        let mut wrong = FxHashSet::default();
+        for gate in &self.gates {
+            let is_wrong = match gate.op {
+                _ if gate.out == last => gate.op != Op::Or,
+                _ if gate.out.starts_with('z') => gate.op != Op::Xor,
+                Op::Or => false,
+                Op::Xor if gate.has_input_wires() => {
+                    !gate.is_first_bit() && !consumers(gate.out, Op::Xor)
+                }
+                Op::Xor => !gate.out.starts_with('z'),
+                Op::And if gate.is_first_bit() => false,
+                Op::And => !consumers(gate.out, Op::Or),
+            };
+            if is_wrong {
+                wrong.insert(gate.out);
+            }
+        }
+        wrong
+    }
+
+    fn to_dot(&self, highlight: &FxHashSet<&str>) -> String {
+        let mut dot = String::from("digraph circuit {\n    rankdir=LR;\n");
+        let wires = self
+            .gates
+            .iter()
+            .flat_map(|gate| [gate.a, gate.b, gate.out])
+            .chain(self.inputs.keys().copied())
+            .unique()
+            .sorted();
+        for wire in wires {
+            let style = if highlight.contains(wire) {
+                ", style=filled, fillcolor=tomato"
+            } else {
+                ""
+            };
+            writeln!(dot, "    {wire} [shape=ellipse{style}];").unwrap();
+        }
+        for (i, gate) in self.gates.iter().enumerate() {
+            let shape = match gate.op {
+                Op::And => "box",
+                Op::Or => "diamond",
+                Op::Xor => "hexagon",
+            };
+            writeln!(dot, "    g{i} [label=\"{:?}\", shape={shape}];", gate.op).unwrap();
+            writeln!(dot, "    {} -> g{i};", gate.a).unwrap();
+            writeln!(dot, "    {} -> g{i};", gate.b).unwrap();
+            writeln!(dot, "    g{i} -> {};", gate.out).unwrap();
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+fn eval<'a>(
+    wire: &'a str,
+    drivers: &FxHashMap<&'a str, &Gate<'a>>,
+    values: &mut FxHashMap<&'a str, bool>,
+) -> bool {
+    if let Some(&value) = values.get(wire) {
+        return value;
+    }
+    let gate = drivers
+        .get(wire)
+        .unwrap_or_else(|| panic!("wire {wire} is not driven by any gate"));
+    let value = gate
+        .op
+        .apply(eval(gate.a, drivers, values), eval(gate.b, drivers, values));
+    values.insert(wire, value);
+    value
+}
+
+fn parse_input(input: &str) -> Circuit<'_> {
+    let (inputs, gates) = input.split_once("\n\n").unwrap();
+    let inputs = inputs
+        .lines()
+        .map(|line| {
+            let (wire, value) = line.split_once(": ").unwrap();
+            (wire, value == "1")
+        })
+        .collect();
+    let gates = gates
+        .lines()
+        .map(|line| {
+            let (a, op, b, _, out) = line.split_whitespace().collect_tuple().unwrap();
+            let op = match op {
+                "AND" => Op::And,
+                "OR" => Op::Or,
+                "XOR" => Op::Xor,
+                other => panic!("unsupported gate: {other}"),
+            };
+            Gate { a, op, b, out }
+        })
+        .collect();
+    Circuit { inputs, gates }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part_one() {
+        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Some(2024));
+    }
+
+    #[test]
+    fn test_part_one_small() {
+        let result = part_one(&advent_of_code::template::read_file_part(
+            "examples", DAY, 1,
+        ));
+        assert_eq!(result, Some(4));
+    }
+
+    /// Builds a `bits` wide ripple-carry adder computing `x + y`, with the outputs of `swaps` exchanged.
+    fn ripple_carry_adder(bits: usize, x: u64, y: u64, swaps: &[(&str, &str)]) -> String {
+        let mut input = String::new();
+        for (name, value) in [('x', x), ('y', y)] {
+            for i in 0..bits {
+                writeln!(input, "{name}{i:02}: {}", value >> i & 1).unwrap();
+            }
+        }
+        input.push('\n');
+        let mut gates = vec![
+            "x00 XOR y00 -> z00".to_string(),
+            "x00 AND y00 -> c00".to_string(),
+        ];
+        for i in 1..bits {
+            let carry = format!("c{:02}", i - 1);
+            let out = if i == bits - 1 {
+                format!("z{bits:02}")
+            } else {
+                format!("c{i:02}")
+            };
+            gates.push(format!("x{i:02} XOR y{i:02} -> s{i:02}"));
+            gates.push(format!("x{i:02} AND y{i:02} -> a{i:02}"));
+            gates.push(format!("s{i:02} XOR {carry} -> z{i:02}"));
+            gates.push(format!("s{i:02} AND {carry} -> b{i:02}"));
+            gates.push(format!("a{i:02} OR b{i:02} -> {out}"));
+        }
+        for gate in gates {
+            let gate = swaps.iter().fold(gate, |gate, &(one, two)| {
+                if gate.ends_with(one) {
+                    gate.replace(one, two)
+                } else if gate.ends_with(two) {
+                    gate.replace(two, one)
+                } else {
+                    gate
+                }
+            });
+            writeln!(input, "{gate}").unwrap();
+        }
+        input
+    }
+
+    #[test]
+    fn test_adder() {
+        let input = ripple_carry_adder(8, 173, 99, &[]);
+        assert_eq!(part_one(&input), Some(173 + 99));
+        assert_eq!(part_two(&input), Some(String::new()));
+    }
+
+    #[test]
+    fn test_part_two() {
+        let swaps = [
+            ("s02", "a02"),
+            ("z04", "c04"),
+            ("z06", "b06"),
+            ("c03", "z03"),
+        ];
+        let input = ripple_carry_adder(8, 0, 0, &swaps);
+        let result = part_two(&input);
+        assert_eq!(result, Some("a02,b06,c03,c04,s02,z03,z04,z06".to_string()));
+    }
+
+    #[test]
+    fn test_dot() {
+        let input = advent_of_code::template::read_file_part("examples", DAY, 1);
+        let circuit = parse_input(&input);
+        let dot = circuit.to_dot(&FxHashSet::from_iter(["z02"]));
+        assert!(dot.starts_with("digraph circuit {"));
+        assert!(dot.contains("    z02 [shape=ellipse, style=filled, fillcolor=tomato];"));
+        assert!(dot.contains("    g2 [label=\"Or\", shape=diamond];"));
+        assert!(dot.contains("    x02 -> g2;"));
+    }
+}
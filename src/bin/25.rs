@@ -0,0 +1,108 @@
+advent_of_code::solution!(25);
+
+pub fn part_one(input: &str) -> Option<usize> {
+    let (locks, keys) = parse_input(input);
+    Some(count_fitting(&locks, &keys))
+}
+
+pub fn part_two(_input: &str) -> Option<String> {
+    Some("⭐ Deliver the chronicle! ⭐".to_string())
+}
+
+/// Counts the lock/key pairs whose pins don't overlap in any column.
+fn count_fitting(locks: &[Mask], keys: &[Mask]) -> usize {
+    locks
+        .iter()
+        .map(|lock| keys.iter().filter(|&key| lock & key == 0).count())
+        .sum()
+}
+
+const COLUMNS: usize = 5;
+const SPACE: usize = 5;
+
+/// One bit per cell of the space between the top and bottom row, `SPACE` bits per column.
+type Mask = u32;
+type Heights = [u8; COLUMNS];
+
+/// Pins of a lock hang down from the top, the teeth of a key rise up from the bottom.
+fn to_mask(heights: Heights, is_lock: bool) -> Mask {
+    heights
+        .iter()
+        .enumerate()
+        .fold(0, |mask, (column, &height)| {
+            let filled = (1 << height) - 1;
+            let filled = if is_lock {
+                filled << (SPACE - height as usize)
+            } else {
+                filled
+            };
+            mask | filled << (column * SPACE)
+        })
+}
+
+fn parse_heights(schematic: &str) -> Heights {
+    let mut heights = [0; COLUMNS];
+    for line in schematic.lines().skip(1).take(SPACE) {
+        for (column, c) in line.bytes().enumerate() {
+            heights[column] += (c == b'#') as u8;
+        }
+    }
+    heights
+}
+
+fn parse_input(input: &str) -> (Vec<Mask>, Vec<Mask>) {
+    let mut locks = vec![];
+    let mut keys = vec![];
+    for schematic in input.split("\n\n") {
+        let is_lock = schematic.starts_with("#####");
+        let mask = to_mask(parse_heights(schematic), is_lock);
+        if is_lock {
+            locks.push(mask);
+        } else {
+            keys.push(mask);
+        }
+    }
+    (locks, keys)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part_one() {
+        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Some(3));
+    }
+
+    #[test]
+    fn test_part_two() {
+        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        assert!(result.is_some_and(|message| message.contains('⭐')));
+    }
+
+    #[test]
+    fn test_heights() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let heights: Vec<_> = input.split("\n\n").map(parse_heights).collect();
+        assert_eq!(
+            heights,
+            [
+                [0, 5, 3, 4, 3],
+                [1, 2, 0, 5, 3],
+                [5, 0, 2, 1, 3],
+                [4, 3, 4, 0, 2],
+                [3, 0, 2, 0, 1]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_masks_overlap() {
+        // a lock pin of height 3 and a key tooth of height 2 exactly fill a column.
+        let lock = to_mask([3, 0, 0, 0, 0], true);
+        assert_eq!(lock & to_mask([2, 0, 0, 0, 0], false), 0);
+        assert_ne!(lock & to_mask([3, 0, 0, 0, 0], false), 0);
+        assert_eq!(lock & to_mask([0, 5, 5, 5, 5], false), 0);
+    }
+}
@@ -1,144 +1,179 @@
-use advent_of_code::template::commands::{all, download, read, scaffold, solve, time};
+use advent_of_code::template::commands::{all, completions, download, read, scaffold, solve, time};
 use args::{parse, AppArguments};
 
 #[cfg(feature = "today")]
-use advent_of_code::template::Day;
+use advent_of_code::template::{Day, EXIT_FAILURE};
 #[cfg(feature = "today")]
 use std::process;
 
 mod args {
     use advent_of_code::template::Day;
-    use std::process;
+    use clap::{CommandFactory, Parser, Subcommand};
+    use clap_complete::Shell;
 
+    /// Scaffold, run and benchmark Advent of Code solutions.
+    ///
+    /// Exits with 0 on success, 1 if a command failed and 2 on invalid usage.
+    #[derive(Parser)]
+    #[command(version, propagate_version = true)]
+    struct Cli {
+        #[command(subcommand)]
+        command: AppArguments,
+    }
+
+    #[derive(Subcommand)]
     pub enum AppArguments {
+        /// Download the puzzle description and input of a day.
         Download {
+            /// Day of advent (1-25).
             day: Day,
         },
+        /// Read the puzzle description of a day, downloading it if it is not present yet.
         Read {
+            /// Day of advent (1-25).
             day: Day,
+            /// Print the puzzle instead of opening it in `$PAGER`.
+            #[arg(long)]
+            no_pager: bool,
         },
+        /// Create the solution module and example file of a day.
         Scaffold {
+            /// Day of advent (1-25).
             day: Day,
+            /// Download the puzzle before scaffolding.
+            #[arg(long)]
             download: bool,
+            /// Overwrite an existing solution module.
+            #[arg(long)]
             overwrite: bool,
+            /// Name of the template to scaffold from.
+            #[arg(long, value_name = "NAME")]
+            template: Option<String>,
+            /// Name of the parsed input type used by the template.
+            #[arg(long, value_name = "NAME")]
+            input_type: Option<String>,
         },
+        /// Build and run the solution of a day against the real input.
         Solve {
+            /// Day of advent (1-25).
             day: Day,
+            /// Build with the release profile.
+            #[arg(long, conflicts_with = "profile")]
             release: bool,
+            /// Build with the given cargo profile.
+            #[arg(long, value_name = "NAME")]
+            profile: Option<String>,
+            /// Profile heap allocations with dhat.
+            #[arg(long, conflicts_with_all = ["release", "profile"])]
             dhat: bool,
+            /// Submit the answer of the given part.
+            #[arg(long, value_name = "PART", value_parser = clap::value_parser!(u8).range(1..=2))]
             submit: Option<u8>,
+            /// Run an already built binary without invoking cargo.
+            #[arg(long, conflicts_with = "watch")]
+            no_build: bool,
+            /// Re-run whenever the source or data files of the day change.
+            #[arg(long, conflicts_with = "submit")]
+            watch: bool,
+            /// Arguments passed on to the solution, e.g. `-- --disasm`.
+            #[arg(last = true, value_name = "ARGS")]
+            args: Vec<String>,
         },
+        /// Run the solutions of all days.
         All {
+            /// Build with the release profile.
+            #[arg(long)]
             release: bool,
         },
+        /// Benchmark solutions.
         Time {
-            all: bool,
+            /// Day of advent (1-25), benches days without stored timings if omitted.
+            #[arg(conflicts_with = "all")]
             day: Option<Day>,
+            /// Bench all days.
+            #[arg(long)]
+            all: bool,
+            /// Store the timings in the readme.
+            #[arg(long)]
             store: bool,
         },
+        /// Print a shell completion script to stdout.
+        Completions {
+            /// Shell to generate the completion script for.
+            shell: Shell,
+        },
+        /// Scaffold, download & read the current day.
         #[cfg(feature = "today")]
         Today,
     }
 
-    pub fn parse() -> Result<AppArguments, Box<dyn std::error::Error>> {
-        let mut args = pico_args::Arguments::from_env();
-
-        let app_args = match args.subcommand()?.as_deref() {
-            Some("all") => AppArguments::All {
-                release: args.contains("--release"),
-            },
-            Some("time") => {
-                let all = args.contains("--all");
-                let store = args.contains("--store");
-
-                AppArguments::Time {
-                    all,
-                    day: args.opt_free_from_str()?,
-                    store,
-                }
-            }
-            Some("download") => AppArguments::Download {
-                day: args.free_from_str()?,
-            },
-            Some("read") => AppArguments::Read {
-                day: args.free_from_str()?,
-            },
-            Some("scaffold") => AppArguments::Scaffold {
-                day: args.free_from_str()?,
-                download: args.contains("--download"),
-                overwrite: args.contains("--overwrite"),
-            },
-            Some("solve") => AppArguments::Solve {
-                day: args.free_from_str()?,
-                release: args.contains("--release"),
-                submit: args.opt_value_from_str("--submit")?,
-                dhat: args.contains("--dhat"),
-            },
-            #[cfg(feature = "today")]
-            Some("today") => AppArguments::Today,
-            Some(x) => {
-                eprintln!("Unknown command: {x}");
-                process::exit(1);
-            }
-            None => {
-                eprintln!("No command specified.");
-                process::exit(1);
-            }
-        };
-
-        let remaining = args.finish();
-        if !remaining.is_empty() {
-            eprintln!("Warning: unknown argument(s): {remaining:?}.");
-        }
+    /// Parses the command line, exiting with a usage error (or the help text) if it is invalid.
+    pub fn parse() -> AppArguments {
+        Cli::parse().command
+    }
 
-        Ok(app_args)
+    pub fn command() -> clap::Command {
+        Cli::command()
     }
 }
 
 fn main() {
     match parse() {
-        Err(err) => {
-            eprintln!("Error: {err}");
-            std::process::exit(1);
-        }
-        Ok(args) => match args {
-            AppArguments::All { release } => all::handle(release),
-            AppArguments::Time { day, all, store } => time::handle(day, all, store),
-            AppArguments::Download { day } => download::handle(day),
-            AppArguments::Read { day } => read::handle(day),
-            AppArguments::Scaffold {
-                day,
-                download,
-                overwrite,
-            } => {
-                scaffold::handle(day, overwrite);
-                if download {
-                    download::handle(day);
-                }
+        AppArguments::All { release } => all::handle(release),
+        AppArguments::Time { day, all, store } => time::handle(day, all, store),
+        AppArguments::Download { day } => download::handle(day),
+        AppArguments::Read { day, no_pager } => read::handle(day, no_pager),
+        AppArguments::Scaffold {
+            day,
+            download,
+            overwrite,
+            template,
+            input_type,
+        } => {
+            // download first, so the puzzle title is available to the template.
+            if download {
+                download::handle_for_scaffold(day);
             }
-            AppArguments::Solve {
+            scaffold::handle(day, overwrite, template.as_deref(), input_type.as_deref());
+        }
+        AppArguments::Solve {
+            day,
+            release,
+            profile,
+            dhat,
+            submit,
+            no_build,
+            watch,
+            args,
+        } => {
+            let profile = profile.or_else(|| release.then(|| "release".into()));
+            solve::handle(
                 day,
-                release,
+                profile.as_deref(),
                 dhat,
                 submit,
-            } => solve::handle(day, release, dhat, submit),
-            #[cfg(feature = "today")]
-            AppArguments::Today => {
-                match Day::today() {
-                    Some(day) => {
-                        scaffold::handle(day, false);
-                        download::handle(day);
-                        read::handle(day)
-                    }
-                    None => {
-                        eprintln!(
-                            "`today` command can only be run between the 1st and \
-                            the 25th of december. Please use `scaffold` with a specific day."
-                        );
-                        process::exit(1)
-                    }
-                };
-            }
-        },
+                no_build,
+                watch,
+                &args,
+            );
+        }
+        AppArguments::Completions { shell } => completions::handle(shell, args::command()),
+        #[cfg(feature = "today")]
+        AppArguments::Today => {
+            match Day::today() {
+                Some(day) => {
+                    download::handle_for_scaffold(day);
+                    scaffold::handle(day, false, None, None);
+                    read::handle(day, false)
+                }
+                None => {
+                    eprintln!(
+                        "`today` command can only be run between the 1st and \
+                        the 25th of december. Please use `scaffold` with a specific day."
+                    );
+                    process::exit(EXIT_FAILURE)
+                }
+            };
+        }
     };
 }
@@ -28,11 +28,11 @@ fn aoc_client(day: Day) -> anyhow::Result<AocClient> {
         .map_err(|err| err.into())
 }
 
-fn get_puzzle_path(day: Day) -> String {
+pub fn get_puzzle_path(day: Day) -> String {
     format!("data/puzzles/{day}.md")
 }
 
-fn get_year() -> Option<u16> {
+pub fn get_year() -> Option<u16> {
     match var("AOC_YEAR") {
         Ok(x) => x.parse().ok().or(None),
         Err(_) => None,
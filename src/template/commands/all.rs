@@ -1,5 +1,9 @@
-use crate::template::{all_days, run_multi::run_multi};
+use crate::template::{
+    all_days,
+    run_multi::{exit_if_days_failed, run_multi},
+};
 
 pub fn handle(is_release: bool) {
-    run_multi(&all_days().collect(), is_release, false);
+    let (_, failed) = run_multi(&all_days().collect(), is_release, false);
+    exit_if_days_failed(&failed);
 }
@@ -0,0 +1,9 @@
+use std::io;
+
+use clap::Command;
+use clap_complete::{generate, Shell};
+
+pub fn handle(shell: Shell, mut cmd: Command) {
+    let name = cmd.get_name().to_string();
+    generate(shell, &mut cmd, name, &mut io::stdout());
+}
@@ -1,9 +1,18 @@
-use crate::template::Day;
+use crate::template::{Day, EXIT_FAILURE};
 use std::process;
 
 pub fn handle(day: Day) {
     if let Err(e) = crate::template::aoc_client::download_puzzle(day) {
         eprintln!("failed to call download puzzle: {e}");
-        process::exit(1);
+        process::exit(EXIT_FAILURE);
+    };
+}
+
+/// Downloads the puzzle ahead of scaffolding `day`. Failures only warn, as the scaffold falls back
+/// to a generic title without it.
+pub fn handle_for_scaffold(day: Day) {
+    if let Err(e) = crate::template::aoc_client::download_puzzle(day) {
+        eprintln!("failed to call download puzzle: {e}");
+        eprintln!("Scaffolding day {day} without its puzzle title.");
     };
 }
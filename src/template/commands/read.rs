@@ -1,10 +1,62 @@
-use std::process;
+use std::{
+    env, fs,
+    io::{self, IsTerminal, Write},
+    path::Path,
+    process::{self, Command, Stdio},
+};
 
-use crate::template::Day;
+use crate::template::aoc_client::{download_puzzle, get_puzzle_path};
+use crate::template::{markdown, Day, EXIT_FAILURE};
 
-pub fn handle(day: Day) {
-    if let Err(e) = crate::template::aoc_client::download_puzzle(day) {
-        eprintln!("failed to call aoc-client: {e}");
-        process::exit(1);
+/// Pipes `text` into `$PAGER` (or `less -R`), falling back to printing it directly.
+fn page(text: &str) {
+    let pager = env::var("PAGER").unwrap_or_else(|_| "less -R".into());
+    let mut parts = pager.split_whitespace();
+
+    let child = parts.next().and_then(|program| {
+        Command::new(program)
+            .args(parts)
+            .stdin(Stdio::piped())
+            .spawn()
+            .ok()
+    });
+
+    let Some(mut child) = child else {
+        print!("{text}");
+        return;
+    };
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // the pager may be closed before all of the text was written.
+        let _ = stdin.write_all(text.as_bytes());
+    }
+    let _ = child.wait();
+}
+
+/// Renders the puzzle description of `day`. The description is only downloaded if it is not present yet.
+pub fn handle(day: Day, no_pager: bool) {
+    let path = get_puzzle_path(day);
+
+    if !Path::new(&path).exists() {
+        if let Err(e) = download_puzzle(day) {
+            eprintln!("Failed to download puzzle: {e}");
+            process::exit(EXIT_FAILURE);
+        }
+    }
+
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(e) => {
+            eprintln!("Failed to read puzzle \"{path}\": {e}");
+            process::exit(EXIT_FAILURE);
+        }
     };
+
+    let rendered = markdown::render(&contents);
+
+    if no_pager || !io::stdout().is_terminal() {
+        print!("{rendered}");
+    } else {
+        page(&rendered);
+    }
 }
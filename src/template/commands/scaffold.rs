@@ -1,14 +1,161 @@
 use std::{
-    fs::{File, OpenOptions},
+    borrow::Cow,
+    env,
+    fs::{self, File, OpenOptions},
     io::Write,
+    path::PathBuf,
     process,
 };
 
-use crate::template::Day;
+use crate::template::aoc_client::{get_puzzle_path, get_year};
+use crate::template::{Day, EXIT_FAILURE};
 
 const MODULE_TEMPLATE: &str =
     include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/template.txt"));
 
+/// A template that ships with the repository.
+struct Preset {
+    name: &'static str,
+    input_type: &'static str,
+    contents: &'static str,
+}
+
+const PRESETS: &[Preset] = &[
+    Preset {
+        name: "default",
+        input_type: "Input",
+        contents: MODULE_TEMPLATE,
+    },
+    Preset {
+        name: "grid",
+        input_type: "Grid",
+        contents: include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/src/templates/grid.txt"
+        )),
+    },
+    Preset {
+        name: "graph",
+        input_type: "Graph",
+        contents: include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/src/templates/graph.txt"
+        )),
+    },
+    Preset {
+        name: "parse-once",
+        input_type: "Input",
+        contents: include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/src/templates/parse-once.txt"
+        )),
+    },
+    Preset {
+        name: "string",
+        input_type: "Input",
+        contents: include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/src/templates/string.txt"
+        )),
+    },
+];
+
+/// Template contents together with the default for `%INPUT_TYPE%`.
+struct ModuleTemplate {
+    contents: Cow<'static, str>,
+    input_type: &'static str,
+}
+
+/// Directory that holds user-defined templates (`<name>.txt`).
+/// Can be set with `AOC_TEMPLATE_DIR`, otherwise falls back to the platform config directory.
+fn user_template_dir() -> Option<PathBuf> {
+    if let Ok(dir) = env::var("AOC_TEMPLATE_DIR") {
+        return Some(PathBuf::from(dir));
+    }
+
+    let config_dir = env::var("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|_| env::var("APPDATA").map(PathBuf::from))
+        .or_else(|_| env::var("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .ok()?;
+
+    Some(config_dir.join("advent_of_code").join("templates"))
+}
+
+/// Resolves a template by name. User templates take precedence over the built-in presets.
+fn resolve_template(name: &str) -> Result<ModuleTemplate, String> {
+    let preset = PRESETS.iter().find(|preset| preset.name == name);
+    let input_type = preset.map_or("Input", |preset| preset.input_type);
+
+    if let Some(path) = user_template_dir().map(|dir| dir.join(format!("{name}.txt"))) {
+        if path.is_file() {
+            return fs::read_to_string(&path)
+                .map(|contents| ModuleTemplate {
+                    contents: Cow::Owned(contents),
+                    input_type,
+                })
+                .map_err(|e| format!("could not read template \"{}\": {e}", path.display()));
+        }
+    }
+
+    preset
+        .map(|preset| ModuleTemplate {
+            contents: Cow::Borrowed(preset.contents),
+            input_type,
+        })
+        .ok_or_else(|| {
+            format!(
+                "unknown template \"{name}\", expecting one of: {}",
+                available_templates().join(", ")
+            )
+        })
+}
+
+fn available_templates() -> Vec<String> {
+    let mut names: Vec<String> = PRESETS.iter().map(|p| p.name.to_string()).collect();
+
+    if let Some(Ok(entries)) = user_template_dir().map(fs::read_dir) {
+        for path in entries.filter_map(|entry| entry.ok().map(|e| e.path())) {
+            if path.extension().is_some_and(|ext| ext == "txt") {
+                if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
+                    if !names.iter().any(|name| name == stem) {
+                        names.push(stem.to_string());
+                    }
+                }
+            }
+        }
+    }
+
+    names
+}
+
+/// Extracts the puzzle title from a downloaded puzzle description.
+/// The heading is rendered as e.g. `\--- Day 7: Bridge Repair ---`.
+fn parse_title(markdown: &str) -> Option<&str> {
+    markdown.lines().find_map(|line| {
+        line.trim_start_matches(['#', '\\', ' '])
+            .strip_prefix("--- Day ")?
+            .split_once(": ")?
+            .1
+            .trim_end()
+            .strip_suffix("---")
+            .map(str::trim)
+    })
+}
+
+fn read_title(day: Day) -> Option<String> {
+    let markdown = fs::read_to_string(get_puzzle_path(day)).ok()?;
+    parse_title(&markdown).map(String::from)
+}
+
+fn render_template(template: &str, day: Day, year: u16, title: &str, input_type: &str) -> String {
+    template
+        .replace("%DAY_NUMBER%", &day.into_inner().to_string())
+        .replace("%YEAR%", &year.to_string())
+        .replace("%TITLE%", title)
+        .replace("%INPUT_TYPE%", input_type)
+}
+
 fn safe_create_file(path: &str, overwrite: bool) -> Result<File, std::io::Error> {
     let mut file = OpenOptions::new();
     if overwrite {
@@ -27,29 +174,42 @@ fn create_file(path: &str) -> Result<File, std::io::Error> {
         .open(path)
 }
 
-pub fn handle(day: Day, overwrite: bool) {
+pub fn handle(day: Day, overwrite: bool, template: Option<&str>, input_type: Option<&str>) {
     let example_path = format!("data/examples/{day}.txt");
     let module_path = format!("src/bin/{day}.rs");
 
+    let template = match resolve_template(template.unwrap_or("default")) {
+        Ok(template) => template,
+        Err(e) => {
+            eprintln!("Failed to load template: {e}");
+            process::exit(EXIT_FAILURE);
+        }
+    };
+
+    let title = read_title(day).unwrap_or_else(|| format!("Day {}", day.into_inner()));
+    let contents = render_template(
+        &template.contents,
+        day,
+        get_year().unwrap_or(2024),
+        &title,
+        input_type.unwrap_or(template.input_type),
+    );
+
     let mut file = match safe_create_file(&module_path, overwrite) {
         Ok(file) => file,
         Err(e) => {
             eprintln!("Failed to create module file: {e}");
-            process::exit(1);
+            process::exit(EXIT_FAILURE);
         }
     };
 
-    match file.write_all(
-        MODULE_TEMPLATE
-            .replace("%DAY_NUMBER%", &day.into_inner().to_string())
-            .as_bytes(),
-    ) {
+    match file.write_all(contents.as_bytes()) {
         Ok(()) => {
             println!("Created module file \"{}\"", &module_path);
         }
         Err(e) => {
             eprintln!("Failed to write module contents: {e}");
-            process::exit(1);
+            process::exit(EXIT_FAILURE);
         }
     }
 
@@ -59,10 +219,47 @@ pub fn handle(day: Day, overwrite: bool) {
         }
         Err(e) => {
             eprintln!("Failed to create example file: {e}");
-            process::exit(1);
+            process::exit(EXIT_FAILURE);
         }
     }
 
     println!("---");
     println!("🎄 Type `cargo solve {day}` to run your solution.");
 }
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{parse_title, render_template, resolve_template};
+    use crate::day;
+
+    #[test]
+    fn parses_title_from_markdown() {
+        let markdown = "\\--- Day 7: Bridge Repair ---\n----------\n\nThe Historians take you...";
+        assert_eq!(parse_title(markdown), Some("Bridge Repair"));
+        assert_eq!(
+            parse_title("## --- Day 12: Garden Groups ---"),
+            Some("Garden Groups")
+        );
+        assert_eq!(parse_title("no heading here"), None);
+    }
+
+    #[test]
+    fn renders_all_placeholders() {
+        let rendered = render_template(
+            "solution!(%DAY_NUMBER%); // %YEAR%: %TITLE% -> %INPUT_TYPE%",
+            day!(7),
+            2024,
+            "Bridge Repair",
+            "Grid",
+        );
+        assert_eq!(rendered, "solution!(7); // 2024: Bridge Repair -> Grid");
+    }
+
+    #[test]
+    fn resolves_builtin_presets() {
+        for name in ["default", "grid", "graph", "parse-once", "string"] {
+            assert!(resolve_template(name).is_ok(), "missing preset {name}");
+        }
+        assert!(resolve_template("does-not-exist").is_err());
+    }
+}
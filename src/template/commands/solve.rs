@@ -1,34 +1,144 @@
-use std::process::{Command, Stdio};
+use std::{
+    env,
+    path::{Path, PathBuf},
+    process::{self, Command, ExitStatus, Stdio},
+};
 
-use crate::template::Day;
+use crate::template::{watch::watch, Day, EXIT_FAILURE};
 
-pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
-    let mut cmd_args = vec!["run".to_string(), "--bin".to_string(), day.to_string()];
+/// Directory below `target/` that cargo places artifacts of a profile in.
+fn profile_dir(profile: &str) -> &str {
+    match profile {
+        "dev" | "test" => "debug",
+        "bench" => "release",
+        other => other,
+    }
+}
+
+/// Path of the binary for `day` built with `profile` below `target_dir`.
+fn binary_path(target_dir: &Path, day: Day, profile: &str) -> PathBuf {
+    target_dir
+        .join(profile_dir(profile))
+        .join(format!("{day}{}", env::consts::EXE_SUFFIX))
+}
+
+pub(crate) fn get_path_for_binary(day: Day, profile: &str) -> PathBuf {
+    let target_dir = env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".into());
+    binary_path(Path::new(&target_dir), day, profile)
+}
+
+fn exit_code(status: ExitStatus) -> i32 {
+    // a missing code means the process was terminated by a signal.
+    status.code().unwrap_or(1)
+}
+
+pub(crate) fn build(day: Day, profile: &str, dhat: bool) -> Result<(), String> {
+    let day_padded = day.to_string();
+    let mut cmd_args = vec![
+        "build",
+        "--quiet",
+        "--bin",
+        &day_padded,
+        "--profile",
+        profile,
+    ];
 
     if dhat {
-        cmd_args.extend([
-            "--profile".to_string(),
-            "dhat".to_string(),
-            "--features".to_string(),
-            "dhat-heap".to_string(),
-        ]);
-    } else if release {
-        cmd_args.push("--release".to_string());
+        cmd_args.extend(["--features", "dhat-heap"]);
+    }
+
+    let status = Command::new("cargo")
+        .args(&cmd_args)
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .status()
+        .map_err(|e| format!("could not invoke cargo ({e}), is it installed and on your PATH?"))?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "build of day {day} failed with exit code {}.",
+            exit_code(status)
+        ))
+    }
+}
+
+/// Builds (unless `no_build` is set) and runs the solution for `day`, exiting with its exit code.
+///
+/// `profile` accepts any cargo profile, `dhat` overrides it with the `dhat` profile.
+/// With `watch_files` set, the day is re-run whenever its files change instead.
+/// `args` are passed on to the solution binary, e.g. to select one of its modes.
+pub fn handle(
+    day: Day,
+    profile: Option<&str>,
+    dhat: bool,
+    submit_part: Option<u8>,
+    no_build: bool,
+    watch_files: bool,
+    args: &[String],
+) {
+    let profile = if dhat {
+        "dhat"
+    } else {
+        profile.unwrap_or("dev")
+    };
+
+    if watch_files {
+        watch(day, profile, dhat);
+    }
+
+    if !no_build {
+        if let Err(e) = build(day, profile, dhat) {
+            eprintln!("Failed to build solution: {e}");
+            process::exit(EXIT_FAILURE);
+        }
     }
 
-    cmd_args.push("--".to_string());
+    let binary = get_path_for_binary(day, profile);
+    if !binary.exists() {
+        eprintln!(
+            "No binary found at \"{}\". Run without `--no-build` to build it first.",
+            binary.display()
+        );
+        process::exit(EXIT_FAILURE);
+    }
 
+    let mut cmd = Command::new(&binary);
     if let Some(submit_part) = submit_part {
-        cmd_args.push("--submit".to_string());
-        cmd_args.push(submit_part.to_string());
+        cmd.args(["--submit", &submit_part.to_string()]);
     }
+    cmd.args(args);
 
-    let mut cmd = Command::new("cargo")
-        .args(&cmd_args)
+    match cmd
         .stdout(Stdio::inherit())
         .stderr(Stdio::inherit())
-        .spawn()
-        .unwrap();
+        .status()
+    {
+        Ok(status) if status.success() => {}
+        Ok(status) => process::exit(exit_code(status)),
+        Err(e) => {
+            eprintln!("Failed to run \"{}\": {e}", binary.display());
+            process::exit(EXIT_FAILURE);
+        }
+    }
+}
 
-    cmd.wait().unwrap();
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::binary_path;
+    use crate::day;
+    use std::path::Path;
+
+    #[test]
+    fn resolves_binary_path_for_profiles() {
+        let suffix = std::env::consts::EXE_SUFFIX;
+        let target = Path::new("some/target");
+        let expected = |dir: &str| target.join(dir).join(format!("05{suffix}"));
+
+        assert_eq!(binary_path(target, day!(5), "dev"), expected("debug"));
+        assert_eq!(binary_path(target, day!(5), "release"), expected("release"));
+        assert_eq!(binary_path(target, day!(5), "dhat"), expected("dhat"));
+        assert_eq!(binary_path(target, day!(5), "bench"), expected("release"));
+    }
 }
@@ -1,8 +1,8 @@
-use std::collections::HashSet;
+use std::{collections::HashSet, process};
 
-use crate::template::run_multi::run_multi;
+use crate::template::run_multi::{exit_if_days_failed, run_multi};
 use crate::template::timings::Timings;
-use crate::template::{all_days, readme_benchmarks, Day};
+use crate::template::{all_days, readme_benchmarks, Day, EXIT_FAILURE};
 
 pub fn handle(day: Option<Day>, run_all: bool, store: bool) {
     let stored_timings = Timings::read_from_file();
@@ -21,11 +21,15 @@ pub fn handle(day: Option<Day>, run_all: bool, store: bool) {
         |day| HashSet::from([day]),
     );
 
-    let timings = run_multi(&days_to_run, true, true).unwrap();
+    let (timings, failed) = run_multi(&days_to_run, true, true);
+    let timings = timings.unwrap();
 
     if store {
         let merged_timings = stored_timings.merge(&timings);
-        merged_timings.store_file().unwrap();
+        if let Err(e) = merged_timings.store_file() {
+            eprintln!("Failed to store timings: {e}");
+            process::exit(EXIT_FAILURE);
+        }
 
         println!();
         match readme_benchmarks::update(merged_timings) {
@@ -34,7 +38,11 @@ pub fn handle(day: Option<Day>, run_all: bool, store: bool) {
             }
             Err(_) => {
                 eprintln!("Failed to store updated benchmarks.");
+                process::exit(EXIT_FAILURE);
             }
         }
     }
+
+    // the timings of successful days are stored even if others failed.
+    exit_if_days_failed(&failed);
 }
@@ -0,0 +1,166 @@
+//! Renders puzzle descriptions (as saved by `aoc-client`) for the terminal.
+//!
+//! Only the subset of markdown that `html2md` emits for puzzle pages is supported:
+//! ATX and setext headings, fenced code blocks, lists, inline code, emphasis, links and escapes.
+use crate::template::{ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+
+const CODE_INDENT: &str = "    ";
+
+/// Renders `markdown` into a string containing ANSI escape codes.
+pub fn render(markdown: &str) -> String {
+    let mut out = String::with_capacity(markdown.len());
+    let mut lines = markdown.lines().peekable();
+    let mut in_code_block = false;
+
+    while let Some(line) = lines.next() {
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+
+        if in_code_block {
+            out.push_str(CODE_INDENT);
+            out.push_str(line);
+        } else if let Some(heading) = atx_heading(line) {
+            push_heading(&mut out, heading);
+        } else if !line.trim().is_empty() && lines.peek().is_some_and(|l| is_setext_underline(l)) {
+            lines.next();
+            push_heading(&mut out, line);
+        } else {
+            out.push_str(&render_inline(line));
+        }
+        out.push('\n');
+    }
+
+    out
+}
+
+fn atx_heading(line: &str) -> Option<&str> {
+    let text = line.trim_start_matches('#');
+    let level = line.len() - text.len();
+    ((1..=6).contains(&level) && text.starts_with(' ')).then(|| text.trim())
+}
+
+fn is_setext_underline(line: &str) -> bool {
+    let line = line.trim();
+    line.len() >= 3 && (line.bytes().all(|b| b == b'-') || line.bytes().all(|b| b == b'='))
+}
+
+fn push_heading(out: &mut String, text: &str) {
+    out.push_str(ANSI_BOLD);
+    out.push_str(&unescape(text));
+    out.push_str(ANSI_RESET);
+}
+
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => out.extend(chars.next()),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Renders emphasis as bold (puzzles use it to highlight the important bits), inline code as
+/// italic and reduces links to their text.
+fn render_inline(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.char_indices().peekable();
+    let mut emphasis = false;
+
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => out.extend(chars.next().map(|(_, c)| c)),
+            '`' => match line[i + 1..].find('`') {
+                Some(len) => {
+                    out.push_str(ANSI_ITALIC);
+                    out.push_str(&line[i + 1..i + 1 + len]);
+                    out.push_str(ANSI_RESET);
+                    if emphasis {
+                        out.push_str(ANSI_BOLD);
+                    }
+                    while chars.next_if(|&(j, _)| j <= i + 1 + len).is_some() {}
+                }
+                None => out.push(c),
+            },
+            '*' | '_' if is_emphasis_marker(line, i) => {
+                // `**` (strong) is rendered the same way as `*` (emphasis).
+                chars.next_if(|&(_, next)| next == c);
+                emphasis = !emphasis;
+                out.push_str(if emphasis { ANSI_BOLD } else { ANSI_RESET });
+            }
+            '[' => match link_text(&line[i..]) {
+                Some((text, len)) => {
+                    out.push_str(&render_inline(text));
+                    while chars.next_if(|&(j, _)| j < i + len).is_some() {}
+                }
+                None => out.push(c),
+            },
+            c => out.push(c),
+        }
+    }
+
+    if emphasis {
+        out.push_str(ANSI_RESET);
+    }
+    out
+}
+
+/// A marker opens or closes emphasis if it is not surrounded by whitespace on both sides,
+/// which keeps list bullets (`* item`) and stray asterisks intact.
+fn is_emphasis_marker(line: &str, i: usize) -> bool {
+    let before = line[..i].chars().next_back();
+    let after = line[i + 1..].chars().next();
+    let is_space = |c: Option<char>| c.is_none_or(char::is_whitespace);
+    !(is_space(before) && is_space(after))
+}
+
+/// Parses `[text](url)` and `[text][ref]`, returning the text and the length of the whole link.
+fn link_text(s: &str) -> Option<(&str, usize)> {
+    let close = s.find(']')?;
+    let text = &s[1..close];
+    let rest = &s[close + 1..];
+    let end = match rest.chars().next()? {
+        '(' => rest.find(')')?,
+        '[' => rest.find(']')?,
+        _ => return None,
+    };
+    Some((text, close + 1 + end + 1))
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{render, render_inline};
+    use crate::template::{ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+
+    #[test]
+    fn renders_setext_heading() {
+        let rendered = render("\\--- Day 1: Historian Hysteria ---\n----------\n\nText");
+        assert_eq!(
+            rendered,
+            format!("{ANSI_BOLD}--- Day 1: Historian Hysteria ---{ANSI_RESET}\n\nText\n")
+        );
+    }
+
+    #[test]
+    fn renders_code_blocks_verbatim() {
+        let rendered = render("```\n3   4\n*a*\n```\n");
+        assert_eq!(rendered, "    3   4\n    *a*\n");
+    }
+
+    #[test]
+    fn renders_inline_markup() {
+        assert_eq!(
+            render_inline("the *total* of `x`"),
+            format!("the {ANSI_BOLD}total{ANSI_RESET} of {ANSI_ITALIC}x{ANSI_RESET}")
+        );
+        assert_eq!(
+            render_inline("see [the list](/2024/day/1/input) \\*now\\*"),
+            "see the list *now*"
+        );
+        assert_eq!(render_inline("* item"), "* item");
+    }
+}
@@ -1,4 +1,4 @@
-use std::{env, fs};
+use std::{env, fmt::Display, fs, str::FromStr};
 
 pub mod aoc_client;
 pub mod commands;
@@ -7,14 +7,19 @@ pub mod runner;
 pub use day::*;
 
 mod day;
+mod markdown;
 mod readme_benchmarks;
 mod run_multi;
 mod timings;
+mod watch;
 
 pub const ANSI_ITALIC: &str = "\x1b[3m";
 pub const ANSI_BOLD: &str = "\x1b[1m";
 pub const ANSI_RESET: &str = "\x1b[0m";
 
+/// Exit code of a command that failed. Invalid usage exits with `2`.
+pub const EXIT_FAILURE: i32 = 1;
+
 /// Helper function that reads a text file to a string.
 #[must_use]
 pub fn read_file(folder: &str, day: Day) -> String {
@@ -36,9 +41,44 @@ pub fn read_file_part(folder: &str, day: Day, part: u8) -> String {
     f.expect("could not open input file")
 }
 
+/// Parses the value following every `flag` in `args`, e.g. `--break 4` for solution modes that take
+/// arguments. Fails if a flag has no value (or another flag follows it) or if a value is invalid.
+pub fn flag_values<T>(args: &[String], flag: &str) -> Result<Vec<T>, String>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    args.iter()
+        .enumerate()
+        .filter(|(_, arg)| *arg == flag)
+        .map(|(i, _)| match args.get(i + 1) {
+            Some(value) if !value.starts_with("--") => value
+                .parse()
+                .map_err(|e| format!("invalid value `{value}` for `{flag}`: {e}")),
+            _ => Err(format!("`{flag}` expects a value")),
+        })
+        .collect()
+}
+
+/// Like [`flag_values`], for a flag that may be passed at most once.
+pub fn flag_value<T>(args: &[String], flag: &str) -> Result<Option<T>, String>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let mut values = flag_values(args, flag)?;
+    if values.len() > 1 {
+        return Err(format!("`{flag}` can only be passed once"));
+    }
+    Ok(values.pop())
+}
+
 /// Creates the constant `DAY` and sets up the input and runner for each part.
 ///
 /// The optional, second parameter (1 or 2) allows you to only run a single part of the solution.
+///
+/// Additional modes can be registered with `modes: { "--flag" => function }`. If the flag is passed to
+/// the binary (e.g. `cargo solve 17 -- --flag`), `function(&input)` is called instead of the parts.
 #[macro_export]
 macro_rules! solution {
     ($day:expr) => {
@@ -50,8 +90,11 @@ macro_rules! solution {
     ($day:expr, 2) => {
         $crate::solution!(@impl $day, [part_two, 2]);
     };
+    ($day:expr, modes: { $( $flag:literal => $mode:expr ),+ $(,)? }) => {
+        $crate::solution!(@impl $day, [part_one, 1] [part_two, 2]; $( [$flag, $mode] )+);
+    };
 
-    (@impl $day:expr, $( [$func:expr, $part:expr] )*) => {
+    (@impl $day:expr, $( [$func:expr, $part:expr] )* $(; $( [$flag:literal, $mode:expr] )+ )?) => {
         /// The current day.
         const DAY: $crate::template::Day = $crate::day!($day);
 
@@ -62,8 +105,39 @@ macro_rules! solution {
         fn main() -> anyhow::Result<()> {
             use $crate::template::runner::*;
             let input = $crate::template::aoc_client::read_input(DAY)?;
+            $($(
+                if std::env::args().any(|arg| arg == $flag) {
+                    $mode(&input);
+                    return Ok(());
+                }
+            )+)?
             $( run_part($func, &input, DAY, $part); )*
             Ok(())
         }
     };
 }
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{flag_value, flag_values};
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn parses_flag_values() {
+        let args = args(&["07", "--n", "4", "--json", "--n", "8"]);
+        assert_eq!(flag_values::<u8>(&args, "--n"), Ok(vec![4, 8]));
+        assert_eq!(flag_values::<u8>(&args, "--m"), Ok(vec![]));
+        assert_eq!(flag_value::<u8>(&args, "--m"), Ok(None));
+        assert!(flag_value::<u8>(&args, "--n").is_err());
+    }
+
+    #[test]
+    fn rejects_missing_flag_values() {
+        assert!(flag_value::<u8>(&args(&["07", "--n"]), "--n").is_err());
+        assert!(flag_value::<u8>(&args(&["07", "--n", "--json"]), "--n").is_err());
+        assert!(flag_value::<u8>(&args(&["07", "--n", "x"]), "--n").is_err());
+    }
+}
@@ -1,14 +1,27 @@
-use std::{collections::HashSet, io};
+use std::{
+    collections::HashSet,
+    io,
+    process::{self, ExitStatus},
+};
 
-use crate::template::{Day, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+use crate::template::{Day, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET, EXIT_FAILURE};
 
 use super::{
     all_days,
     timings::{Timing, Timings},
 };
 
-pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -> Option<Timings> {
+/// Runs all `days_to_run` in order, returning the timings of the successful days if `is_timed` is set.
+///
+/// A failing day does not stop the remaining days from running, the days that failed are returned
+/// alongside the timings.
+pub fn run_multi(
+    days_to_run: &HashSet<Day>,
+    is_release: bool,
+    is_timed: bool,
+) -> (Option<Timings>, Vec<Day>) {
     let mut timings: Vec<Timing> = Vec::with_capacity(days_to_run.len());
+    let mut failed = vec![];
 
     let mut need_space = false;
 
@@ -24,7 +37,18 @@ pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -
             println!("{ANSI_BOLD}Day {day}{ANSI_RESET}");
             println!("------");
 
-            let output = child_commands::run_solution(day, is_timed, is_release).unwrap();
+            let output = match child_commands::run_solution(day, is_timed, is_release) {
+                Ok(output) => output,
+                Err(Error::Failed(status)) => {
+                    eprintln!("Solution of day {day} failed ({status}).");
+                    failed.push(day);
+                    return;
+                }
+                Err(e) => {
+                    eprintln!("Failed to run solution of day {day}: {e:?}");
+                    process::exit(EXIT_FAILURE);
+                }
+            };
 
             if output.is_empty() {
                 println!("Not solved.");
@@ -34,7 +58,7 @@ pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -
             }
         });
 
-    if is_timed {
+    let timings = if is_timed {
         let timings = Timings { data: timings };
         let total_millis = timings.total_millis();
         println!(
@@ -43,7 +67,19 @@ pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -
         Some(timings)
     } else {
         None
+    };
+
+    (timings, failed)
+}
+
+/// Prints which days failed to run and exits with a failure code, if there are any.
+pub fn exit_if_days_failed(failed: &[Day]) {
+    if failed.is_empty() {
+        return;
     }
+    let days: Vec<String> = failed.iter().map(ToString::to_string).collect();
+    eprintln!("\nFailed days: {}.", days.join(", "));
+    process::exit(EXIT_FAILURE);
 }
 
 #[allow(dead_code)]
@@ -51,6 +87,8 @@ pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -
 pub enum Error {
     BrokenPipe,
     IO(io::Error),
+    /// The solution ran but exited unsuccessfully, e.g. because it failed to build or panicked.
+    Failed(ExitStatus),
 }
 
 impl From<std::io::Error> for Error {
@@ -96,14 +134,15 @@ pub mod child_commands {
             args.push("--time");
         }
 
+        run_command(Command::new("cargo").args(&args))
+    }
+
+    /// Runs `cmd` and returns its stdout lines, failing if it does not exit successfully.
+    fn run_command(cmd: &mut Command) -> Result<Vec<String>, Error> {
         // spawn child command with piped stdout/stderr.
         // forward output to stdout/stderr while grabbing stdout lines.
 
-        let mut cmd = Command::new("cargo")
-            .args(&args)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()?;
+        let mut cmd = cmd.stdout(Stdio::piped()).stderr(Stdio::piped()).spawn()?;
 
         let stdout = BufReader::new(cmd.stdout.take().ok_or(super::Error::BrokenPipe)?);
         let stderr = BufReader::new(cmd.stderr.take().ok_or(super::Error::BrokenPipe)?);
@@ -123,9 +162,13 @@ pub mod child_commands {
         }
 
         thread.join().unwrap();
-        cmd.wait()?;
+        let status = cmd.wait()?;
 
-        Ok(output)
+        if status.success() {
+            Ok(output)
+        } else {
+            Err(Error::Failed(status))
+        }
     }
 
     pub fn parse_exec_time(output: &[String], day: Day) -> super::Timing {
@@ -205,9 +248,23 @@ pub mod child_commands {
 
     #[cfg(feature = "test_lib")]
     mod tests {
-        use super::parse_exec_time;
+        use super::{parse_exec_time, run_command};
 
         use crate::day;
+        use crate::template::run_multi::Error;
+        use std::process::Command;
+
+        #[test]
+        fn collects_output_of_successful_commands() {
+            let output = run_command(Command::new("rustc").arg("--version")).unwrap();
+            assert!(output[0].starts_with("rustc "));
+        }
+
+        #[test]
+        fn fails_on_unsuccessful_commands() {
+            let result = run_command(Command::new("rustc").arg("--no-such-flag"));
+            assert!(matches!(result, Err(Error::Failed(status)) if !status.success()));
+        }
 
         #[test]
         fn parses_execution_times() {
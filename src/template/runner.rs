@@ -20,6 +20,18 @@ pub fn run_part<I: Clone, T: Display>(func: impl Fn(I) -> Option<T>, input: I, d
     }
 }
 
+/// Benches `func` as `--time` does, whether the flag is passed or not, and prints its result
+/// labelled with `label`. Used to compare alternative implementations of a part on the same input.
+pub fn bench_labelled<I: Clone, T: Display>(func: impl Fn(I) -> Option<T>, input: I, label: &str) {
+    let timer = Instant::now();
+    let result = func(input.clone());
+    let base_time = timer.elapsed();
+
+    print_result(&result, label, "");
+    let (duration, samples) = bench(&func, input, &base_time);
+    print_result(&result, label, &format_duration(&duration, samples));
+}
+
 /// Run a solution part. The behavior differs depending on whether we are running a release or debug build:
 ///  1. in debug, the function is executed once.
 ///  2. in release, the function is benched (approx. 1 second of execution time or 10 samples, whatever take longer.)
@@ -0,0 +1,274 @@
+//! Re-runs a single day whenever its source or data files change.
+//!
+//! Changes are detected by polling modification times, every run builds the day,
+//! runs its example tests and its real input and prints a compact summary
+//! compared against the previous run.
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use crate::template::commands::solve::{build, get_path_for_binary};
+use crate::template::run_multi::get_path_for_bin;
+use crate::template::{Day, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+type Fingerprint = Vec<(PathBuf, Option<SystemTime>)>;
+
+/// Outcome of a single watch iteration.
+#[derive(Debug, Default, Clone, PartialEq)]
+struct Report {
+    build_ok: bool,
+    tests: Vec<(String, bool)>,
+    answers: Vec<(String, String)>,
+}
+
+/// Watches the source and example files of `day` and re-runs it on every change. Never returns.
+pub fn watch(day: Day, profile: &str, dhat: bool) -> ! {
+    let mut last_seen = fingerprint(day);
+    let mut previous: Option<Report> = None;
+
+    loop {
+        println!(
+            "{ANSI_BOLD}Day {day}{ANSI_RESET} {ANSI_ITALIC}(watching for changes){ANSI_RESET}"
+        );
+        println!("------");
+
+        let report = run(day, profile, dhat);
+        print_summary(&report, previous.as_ref());
+        previous = Some(report);
+        println!();
+
+        loop {
+            thread::sleep(POLL_INTERVAL);
+            let next = fingerprint(day);
+            if next != last_seen {
+                last_seen = next;
+                break;
+            }
+        }
+    }
+}
+
+fn watched_files(day: Day) -> Vec<PathBuf> {
+    let mut files = vec![PathBuf::from(get_path_for_bin(day))];
+
+    // examples may be split into parts, e.g. `03-1.txt`.
+    for folder in ["data/examples", "data/inputs"] {
+        if let Ok(entries) = fs::read_dir(folder) {
+            files.extend(
+                entries
+                    .filter_map(|entry| entry.ok().map(|e| e.path()))
+                    .filter(|path| is_data_file(path, day)),
+            );
+        }
+    }
+
+    files.sort();
+    files
+}
+
+fn is_data_file(path: &Path, day: Day) -> bool {
+    let day = day.to_string();
+    path.extension().is_some_and(|ext| ext == "txt")
+        && path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .is_some_and(|stem| stem == day || stem.starts_with(&format!("{day}-")))
+}
+
+fn fingerprint(day: Day) -> Fingerprint {
+    watched_files(day)
+        .into_iter()
+        .map(|path| {
+            let modified = fs::metadata(&path).and_then(|m| m.modified()).ok();
+            (path, modified)
+        })
+        .collect()
+}
+
+fn run(day: Day, profile: &str, dhat: bool) -> Report {
+    if let Err(e) = build(day, profile, dhat) {
+        eprintln!("{e}");
+        return Report::default();
+    }
+
+    Report {
+        build_ok: true,
+        tests: run_examples(day, profile),
+        answers: run_input(day, profile),
+    }
+}
+
+fn run_examples(day: Day, profile: &str) -> Vec<(String, bool)> {
+    let output = Command::new("cargo")
+        .args(["test", "--bin", &day.to_string(), "--profile", profile])
+        .stderr(Stdio::null())
+        .output();
+
+    let Ok(output) = output else {
+        eprintln!("Failed to run example tests.");
+        return vec![];
+    };
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let tests = parse_test_results(&stdout);
+
+    if tests.iter().any(|(_, passed)| !passed) {
+        print_failures(&stdout);
+    }
+
+    tests
+}
+
+fn run_input(day: Day, profile: &str) -> Vec<(String, String)> {
+    let output = Command::new(get_path_for_binary(day, profile))
+        .stderr(Stdio::inherit())
+        .output();
+
+    match output {
+        Ok(output) => parse_answers(&String::from_utf8_lossy(&output.stdout)),
+        Err(e) => {
+            eprintln!("Failed to run solution: {e}");
+            vec![]
+        }
+    }
+}
+
+/// Parses the lines `test <name> ... ok|FAILED` of the libtest output.
+fn parse_test_results(output: &str) -> Vec<(String, bool)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (name, result) = line.strip_prefix("test ")?.split_once(" ... ")?;
+            match result.trim() {
+                "ok" => Some((name.to_string(), true)),
+                "FAILED" => Some((name.to_string(), false)),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Prints the captured output of failed tests, which libtest lists under `failures:`.
+fn print_failures(output: &str) {
+    output
+        .lines()
+        .skip_while(|line| *line != "failures:")
+        .skip(1)
+        .take_while(|line| *line != "failures:")
+        .for_each(|line| println!("{line}"));
+}
+
+/// Parses `Part <n>: <answer> (<duration>)` lines as printed by the runner.
+///
+/// The runner prints every answer once before timing it and overwrites it with `\r`
+/// afterwards, so only the last segment of a line is the final one.
+fn parse_answers(output: &str) -> Vec<(String, String)> {
+    output
+        .lines()
+        .filter_map(|line| line.rsplit('\r').next())
+        .map(strip_ansi)
+        .filter_map(|line| {
+            let (part, rest) = line.split_once(": ")?;
+            if !part.starts_with("Part ") {
+                return None;
+            }
+            let answer = rest.rsplit_once(" (").map_or(rest, |(a, _)| a);
+            Some((part.to_string(), answer.trim().to_string()))
+        })
+        .collect()
+}
+
+fn strip_ansi(line: &str) -> String {
+    let mut stripped = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            chars
+                .by_ref()
+                .take_while(|c| !c.is_ascii_alphabetic())
+                .for_each(drop);
+        } else {
+            stripped.push(c);
+        }
+    }
+    stripped
+}
+
+fn print_summary(report: &Report, previous: Option<&Report>) {
+    if !report.build_ok {
+        println!("✖ build failed");
+        return;
+    }
+
+    for (name, passed) in &report.tests {
+        let before = previous.and_then(|p| p.tests.iter().find(|(n, _)| n == name));
+        let mark = if *passed { "✔" } else { "✖" };
+        let change = match before {
+            Some((_, was)) if was != passed => {
+                if *passed {
+                    " (fixed)"
+                } else {
+                    " (regressed)"
+                }
+            }
+            None if previous.is_some_and(|p| p.build_ok) => " (new)",
+            _ => "",
+        };
+        println!("{mark} {name}{change}");
+    }
+
+    for (part, answer) in &report.answers {
+        let before = previous.and_then(|p| p.answers.iter().find(|(n, _)| n == part));
+        match before {
+            Some((_, was)) if was != answer => {
+                println!("{part}: {ANSI_BOLD}{answer}{ANSI_RESET} (was {was})");
+            }
+            _ => println!("{part}: {ANSI_BOLD}{answer}{ANSI_RESET}"),
+        }
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{is_data_file, parse_answers, parse_test_results};
+    use crate::day;
+    use std::path::Path;
+
+    #[test]
+    fn parses_test_results() {
+        let output = "\nrunning 2 tests\ntest tests::test_part_one ... ok\ntest tests::test_part_two ... FAILED\n\ntest result: FAILED. 1 passed; 1 failed;";
+        assert_eq!(
+            parse_test_results(output),
+            vec![
+                ("tests::test_part_one".to_string(), true),
+                ("tests::test_part_two".to_string(), false)
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_answers() {
+        let output = "Part 1: \x1b[1m42\x1b[0m\rPart 1: \x1b[1m42\x1b[0m (1.2ms)\nPart 2: ✖\rPart 2: ✖             \n";
+        assert_eq!(
+            parse_answers(output),
+            vec![
+                ("Part 1".to_string(), "42".to_string()),
+                ("Part 2".to_string(), "✖".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn matches_data_files_of_day() {
+        assert!(is_data_file(Path::new("data/examples/03.txt"), day!(3)));
+        assert!(is_data_file(Path::new("data/examples/03-2.txt"), day!(3)));
+        assert!(!is_data_file(Path::new("data/examples/13.txt"), day!(3)));
+        assert!(!is_data_file(Path::new("data/examples/03.md"), day!(3)));
+    }
+}